@@ -21,10 +21,23 @@
 //!
 //!  - `init` - can be called only from the account owner
 //!  - `apply` - can be called only by a customer
-//!  - `get_info` - can be called only by a customer or the restaurateur
+//!  - `pay_for` - can be called by anyone to pay the share of a customer
+//!  - `get_info` - can be called only by a customer or the restaurateur,
+//!    the restaurateur gets the whole contract information
+//!    a customer gets only its own entry and the aggregate state
+//!  - `public_summary` - can be called by anyone, returns only the aggregate state
 //!  - `close` - can be called only by the restaurateur,
-//!       transfer the contract asset to the restaurateur
-//!       close the contract if all the customers have been paid
+//!    transfer the contract asset to the restaurateur
+//!    close the contract if all the customers have been paid
+//!  - `cancel` - can be called only by the restaurateur,
+//!    refund every paid share not yet collected to the account that paid it
+//!    and cancel the contract
+//!
+//! ### Events
+//!
+//!  - `customer_paid` - a customer share has been paid
+//!  - `bill_closed` - the restaurateur collected the paid shares
+//!  - `refund_issued` - a paid share has been refunded to its payer
 
 use trinci_sdk::{
     rmp_deserialize, rmp_serialize_named, AppContext, PackedValue, WasmError, WasmResult,
@@ -33,7 +46,19 @@ use trinci_sdk::{
 mod types;
 use types::*;
 
-trinci_sdk::app_export!(init, apply, get_info, close);
+const PAID_EVENT: &str = "customer_paid";
+const CLOSED_EVENT: &str = "bill_closed";
+const REFUND_EVENT: &str = "refund_issued";
+
+trinci_sdk::app_export!(
+    init,
+    apply,
+    pay_for,
+    get_info,
+    public_summary,
+    close,
+    cancel
+);
 
 /// Init method
 fn init(ctx: AppContext, args: InitArgs) -> WasmResult<()> {
@@ -47,6 +72,21 @@ fn init(ctx: AppContext, args: InitArgs) -> WasmResult<()> {
     trinci_sdk::store_account_data_mp!("config", &args)
 }
 
+/// Build the aggregate state of the contract
+fn summary<'a>(config: &InitArgs<'a>) -> Summary<'a> {
+    let customers = config.customers.len() as u64;
+    let paid = config.customers.values().filter(|&&val| val).count() as u64;
+
+    Summary {
+        asset: config.asset,
+        part: config.part,
+        customers,
+        paid,
+        total: customers * config.part,
+        status: config.status,
+    }
+}
+
 /// Get_Info method
 fn get_info(ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
     // Load the contract configuration
@@ -56,24 +96,44 @@ fn get_info(ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
         Err(_) => return Err(WasmError::new("not initialized")),
     };
 
-    // Checks on the authorization
-    let mut auth_list = vec![config.restaurateur];
-    config
-        .customers
-        .iter()
-        .for_each(|(&customer, _)| auth_list.push(customer));
+    // The restaurateur gets the whole configuration
+    if ctx.caller == config.restaurateur {
+        let buf = rmp_serialize_named(&config)?;
+        return Ok(PackedValue(buf));
+    }
 
-    match auth_list.iter().find(|&&elem| elem == ctx.caller) {
-        Some(_) => {
-            let buf = rmp_serialize_named(&config)?;
+    // A customer gets only its own entry
+    match config.customers.get(ctx.caller) {
+        Some(&paid) => {
+            let info = CustomerInfo {
+                restaurateur: config.restaurateur,
+                customer: ctx.caller,
+                paid,
+                payer: config.payers.get(ctx.caller).copied(),
+                summary: summary(&config),
+            };
+            let buf = rmp_serialize_named(&info)?;
             Ok(PackedValue(buf))
         }
         None => Err(WasmError::new("not authorized")),
     }
 }
 
-/// Apply method
-fn apply(ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
+/// Public_Summary method
+fn public_summary(_ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
+    // Load the contract configuration
+    let buf = trinci_sdk::load_data("config");
+    let config: InitArgs = match rmp_deserialize(&buf) {
+        Ok(val) => val,
+        Err(_) => return Err(WasmError::new("not initialized")),
+    };
+
+    let buf = rmp_serialize_named(&summary(&config))?;
+    Ok(PackedValue(buf))
+}
+
+/// Pay the `customer` share taking the asset from the `payer` account
+fn pay(ctx: &AppContext, payer: &str, customer: &str) -> WasmResult<()> {
     // Load the contract configuration
     let buf = trinci_sdk::load_data("config");
     let mut config: InitArgs = match rmp_deserialize(&buf) {
@@ -85,24 +145,40 @@ fn apply(ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
         return Err(WasmError::new("contract closed"));
     }
 
-    // Check if the caller is in the list and have already paid
-    if let Some(val) = config.customers.get_mut(ctx.caller) {
-        if !*val {
+    // Check if the customer is in the list and have already paid
+    match config.customers.get_mut(customer) {
+        Some(val) if !*val => {
             // Make the payment
-            trinci_sdk::asset_transfer(ctx.caller, ctx.owner, config.asset, config.part)
+            trinci_sdk::asset_transfer(payer, ctx.owner, config.asset, config.part)
                 .map_err(|_| WasmError::new("transfer from caller failed"))?;
             *val = true;
-        } else {
-            return Err(WasmError::new("already paid"));
         }
-    } else {
-        return Err(WasmError::new("not authorized"));
+        Some(_) => return Err(WasmError::new("already paid")),
+        None => return Err(WasmError::new("not authorized")),
     };
 
-    // Store the config and exit
+    // Record who paid the share
+    config.payers.insert(customer, payer);
+
+    // Store the config
     trinci_sdk::store_account_data_mp!("config", &config)?;
 
-    Ok(())
+    let event = PaidEvent {
+        customer,
+        payer,
+        units: config.part,
+    };
+    trinci_sdk::emit_data_mp!(PAID_EVENT, &event)
+}
+
+/// Apply method
+fn apply(ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
+    pay(&ctx, ctx.caller, ctx.caller)
+}
+
+/// Pay_For method
+fn pay_for(ctx: AppContext, args: PayForArgs) -> WasmResult<()> {
+    pay(&ctx, ctx.caller, args.customer)
 }
 
 /// Close method
@@ -138,14 +214,83 @@ fn close(ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
     // Lock again the asset
     trinci_sdk::asset_lock(config.asset, ctx.owner, trinci_sdk::tai::LockType::Withdraw)?;
 
+    // Record the shares swept to the restaurateur
+    for (&customer, _) in config.customers.iter().filter(|(_, paid)| **paid) {
+        config.collected.insert(customer);
+    }
+
     // If all the customers have paid set the status to close
     if config.customers.values().all(|&val| val) {
         //  All the customers have paid
         config.status = "close";
-        trinci_sdk::store_account_data_mp!("config", &config)?;
     }
+    trinci_sdk::store_account_data_mp!("config", &config)?;
+
+    let event = ClosedEvent {
+        restaurateur: config.restaurateur,
+        amount,
+        status: config.status,
+    };
+    trinci_sdk::emit_data_mp!(CLOSED_EVENT, &event)
+}
+
+/// Cancel method
+fn cancel(ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
+    // Load the contract configuration
+    let buf = trinci_sdk::load_data("config");
+    let mut config: InitArgs = match rmp_deserialize(&buf) {
+        Ok(val) => val,
+        Err(_) => return Err(WasmError::new("not initialized")),
+    };
+
+    // Check if the caller is the restaurateur
+    if ctx.caller != config.restaurateur {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    // Check if the contract is still opened
+    if config.status != "open" {
+        return Err(WasmError::new("contract closed"));
+    }
+
+    // Unlock the asset
+    trinci_sdk::asset_lock(config.asset, ctx.owner, trinci_sdk::tai::LockType::None)?;
+
+    // Only the shares not yet collected by the restaurateur can be refunded
+    let collected = &config.collected;
+    let refundable = config
+        .customers
+        .iter()
+        .filter(|(customer, paid)| **paid && !collected.contains(*customer))
+        .count() as u64;
+    let amount: u64 = trinci_sdk::asset_balance(config.asset)?;
+    if amount < refundable * config.part {
+        return Err(WasmError::new("insufficient balance"));
+    }
+
+    // Refund every uncollected share to the account that paid it
+    for (&customer, paid) in config
+        .customers
+        .iter_mut()
+        .filter(|(customer, paid)| **paid && !collected.contains(*customer))
+    {
+        let payer = config.payers.remove(customer).unwrap_or(customer);
+        trinci_sdk::asset_transfer(ctx.owner, payer, config.asset, config.part)?;
+        *paid = false;
+
+        let event = RefundEvent {
+            customer,
+            payer,
+            units: config.part,
+        };
+        trinci_sdk::emit_data_mp!(REFUND_EVENT, &event)?;
+    }
+
+    // Lock again the asset
+    trinci_sdk::asset_lock(config.asset, ctx.owner, trinci_sdk::tai::LockType::Withdraw)?;
 
-    Ok(())
+    config.status = "cancelled";
+    trinci_sdk::store_account_data_mp!("config", &config)
 }
 
 #[cfg(test)]
@@ -154,8 +299,8 @@ mod tests {
     use super::*;
 
     use crate::types::tests::{
-        create_init_args, ASSET_ID, CUSTOMER1_ID, CUSTOMER2_ID, CUSTOMER3_ID, PAY_ID,
-        RESTAURATEUR_ID,
+        create_init_args, create_summary, ASSET_ID, CUSTOMER1_ID, CUSTOMER2_ID, CUSTOMER3_ID,
+        PAY_ID, RESTAURATEUR_ID,
     };
     use trinci_sdk::{not_wasm, rmp_deserialize, rmp_serialize, tai::Asset, value, Value};
 
@@ -209,8 +354,98 @@ mod tests {
 
         // Checks on the contract info
         let restaurateur = data.get(&value!("restaurateur")).unwrap().as_str().unwrap();
+        let customer = data.get(&value!("customer")).unwrap().as_str().unwrap();
+        let summary = data.get(&value!("summary")).unwrap();
+        let customers = summary.get(&value!("customers")).unwrap().as_u64().unwrap();
 
         assert_eq!(restaurateur, RESTAURATEUR_ID);
+        assert_eq!(customer, CUSTOMER1_ID);
+        assert_eq!(customers, 3);
+    }
+
+    #[test]
+    fn test_get_info_hides_other_customers() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, CUSTOMER1_ID);
+
+        // Prepare the account data/config
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER2_ID).unwrap() = true;
+        data.payers.insert(CUSTOMER2_ID, CUSTOMER3_ID);
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        let args = PackedValue::default();
+
+        let res = not_wasm::call_wrap(get_info, ctx, args).unwrap();
+
+        let info: CustomerInfo = rmp_deserialize(&res.0).unwrap();
+
+        // Only the aggregate state of the other customers is visible
+        assert!(!info.paid);
+        assert_eq!(info.payer, None);
+        assert_eq!(info.summary.paid, 1);
+        assert_eq!(info.summary.total, 90);
+        let data: Value = rmp_deserialize(&res.0).unwrap();
+        assert!(data.get(&value!("customers")).is_none());
+        assert!(data.get(&value!("payers")).is_none());
+    }
+
+    #[test]
+    fn test_get_info_from_restaurateur() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, RESTAURATEUR_ID);
+
+        // Prepare the account data/config
+        let data = create_init_args();
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        let args = PackedValue::default();
+
+        let res = not_wasm::call_wrap(get_info, ctx, args).unwrap();
+
+        let data: InitArgs = rmp_deserialize(&res.0).unwrap();
+
+        // Checks on the contract info
+        assert_eq!(data, create_init_args());
+    }
+
+    #[test]
+    fn test_public_summary() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, "unknown");
+
+        // Prepare the account data/config
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER2_ID).unwrap() = true;
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        let args = PackedValue::default();
+
+        let res = not_wasm::call_wrap(public_summary, ctx, args).unwrap();
+
+        let summary: Summary = rmp_deserialize(&res.0).unwrap();
+
+        // Checks on the summary
+        assert_eq!(summary, create_summary());
+    }
+
+    #[test]
+    fn test_public_summary_not_initialized() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, "unknown");
+
+        let args = PackedValue::default();
+
+        let err = not_wasm::call_wrap(public_summary, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not initialized");
     }
 
     #[test]
@@ -271,6 +506,7 @@ mod tests {
         let customer1 = data.customers.get(CUSTOMER1_ID).unwrap();
 
         assert!(customer1);
+        assert_eq!(data.payers.get(CUSTOMER1_ID), Some(&CUSTOMER1_ID));
 
         // Checks on the contract asset
         let asset: Asset = not_wasm::get_account_asset_gen(PAY_ID, ASSET_ID);
@@ -373,6 +609,128 @@ mod tests {
         assert_eq!(err.to_string(), "not authorized");
     }
 
+    #[test]
+    fn test_pay_for() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, CUSTOMER1_ID);
+
+        // Prepare the account data/config
+        let data = create_init_args();
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Give the payer some asset
+        not_wasm::set_account_asset_gen(CUSTOMER1_ID, ASSET_ID, Asset::new(100));
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        let args = PayForArgs {
+            customer: CUSTOMER2_ID,
+        };
+
+        not_wasm::call_wrap(pay_for, ctx, args).unwrap();
+
+        // Checks on the contract account config
+        let buf = not_wasm::get_account_data(PAY_ID, "config");
+        let data: InitArgs = rmp_deserialize(&buf).unwrap();
+
+        assert!(data.customers.get(CUSTOMER2_ID).unwrap());
+        assert!(!data.customers.get(CUSTOMER1_ID).unwrap());
+        assert_eq!(data.payers.get(CUSTOMER2_ID), Some(&CUSTOMER1_ID));
+
+        // Checks on the payer asset
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER1_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 70);
+    }
+
+    #[test]
+    fn test_pay_for_from_not_customer() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, "unknown");
+
+        // Prepare the account data/config
+        let data = create_init_args();
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Give the payer some asset
+        not_wasm::set_account_asset_gen("unknown", ASSET_ID, Asset::new(100));
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        let args = PayForArgs {
+            customer: CUSTOMER3_ID,
+        };
+
+        not_wasm::call_wrap(pay_for, ctx, args).unwrap();
+
+        // Checks on the contract account config
+        let buf = not_wasm::get_account_data(PAY_ID, "config");
+        let data: InitArgs = rmp_deserialize(&buf).unwrap();
+
+        assert!(data.customers.get(CUSTOMER3_ID).unwrap());
+        assert_eq!(data.payers.get(CUSTOMER3_ID), Some(&"unknown"));
+    }
+
+    #[test]
+    fn test_pay_for_on_already_paid() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, CUSTOMER1_ID);
+
+        // Prepare the account data/config
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER2_ID).unwrap() = true;
+
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Give the payer some asset
+        not_wasm::set_account_asset_gen(CUSTOMER1_ID, ASSET_ID, Asset::new(100));
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        let args = PayForArgs {
+            customer: CUSTOMER2_ID,
+        };
+
+        let err = not_wasm::call_wrap(pay_for, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "already paid");
+    }
+
+    #[test]
+    fn test_pay_for_unknown_customer() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, CUSTOMER1_ID);
+
+        // Prepare the account data/config
+        let data = create_init_args();
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Give the payer some asset
+        not_wasm::set_account_asset_gen(CUSTOMER1_ID, ASSET_ID, Asset::new(100));
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        let args = PayForArgs {
+            customer: "unknown",
+        };
+
+        let err = not_wasm::call_wrap(pay_for, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
     #[test]
     fn test_close() {
         // Prepare the environment
@@ -458,6 +816,9 @@ mod tests {
         let data: InitArgs = rmp_deserialize(&buf).unwrap();
 
         assert_eq!(data.status, "open");
+        assert!(data.collected.contains(CUSTOMER1_ID));
+        assert!(data.collected.contains(CUSTOMER3_ID));
+        assert!(!data.collected.contains(CUSTOMER2_ID));
 
         // Checks on the contract asset
         let asset: Asset = not_wasm::get_account_asset_gen(PAY_ID, ASSET_ID);
@@ -503,4 +864,169 @@ mod tests {
 
         assert_eq!(err.to_string(), "not authorized");
     }
+
+    #[test]
+    fn test_cancel() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, RESTAURATEUR_ID);
+
+        // Prepare the account data/config
+        // Customer1 paid its own share, Customer2 paid the Customer3 one
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER1_ID).unwrap() = true;
+        *data.customers.get_mut(CUSTOMER3_ID).unwrap() = true;
+        data.payers.insert(CUSTOMER1_ID, CUSTOMER1_ID);
+        data.payers.insert(CUSTOMER3_ID, CUSTOMER2_ID);
+
+        // Put the asset on the contract account
+        not_wasm::set_account_asset_gen(PAY_ID, ASSET_ID, Asset::new(data.part * 2));
+
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        // Associate a mock asset_lock to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        // Associate a mock balance to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "balance", not_wasm::asset_balance);
+
+        let args = PackedValue::default();
+
+        not_wasm::call_wrap(cancel, ctx, args).unwrap();
+
+        // Checks on the contract account config
+        let buf = not_wasm::get_account_data(PAY_ID, "config");
+        let data: InitArgs = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(data.status, "cancelled");
+        assert!(data.customers.values().all(|&paid| !paid));
+        assert!(data.payers.is_empty());
+
+        // Checks on the contract asset
+        let asset: Asset = not_wasm::get_account_asset_gen(PAY_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 0);
+
+        // Checks on the payers asset
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER1_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 30);
+
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER2_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 30);
+
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER3_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 0);
+    }
+
+    #[test]
+    fn test_cancel_with_collected_shares() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, RESTAURATEUR_ID);
+
+        // Prepare the account data/config
+        // Customer1 share has already been collected, Customer2 paid the Customer3 one later
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER1_ID).unwrap() = true;
+        *data.customers.get_mut(CUSTOMER3_ID).unwrap() = true;
+        data.payers.insert(CUSTOMER1_ID, CUSTOMER1_ID);
+        data.payers.insert(CUSTOMER3_ID, CUSTOMER2_ID);
+        data.collected.insert(CUSTOMER1_ID);
+
+        // Put the uncollected share on the contract account
+        not_wasm::set_account_asset_gen(PAY_ID, ASSET_ID, Asset::new(data.part));
+
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        // Associate a mock asset_lock to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        // Associate a mock balance to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "balance", not_wasm::asset_balance);
+
+        let args = PackedValue::default();
+
+        not_wasm::call_wrap(cancel, ctx, args).unwrap();
+
+        // Checks on the contract account config
+        let buf = not_wasm::get_account_data(PAY_ID, "config");
+        let data: InitArgs = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(data.status, "cancelled");
+        assert_eq!(data.customers.get(CUSTOMER1_ID), Some(&true));
+        assert_eq!(data.customers.get(CUSTOMER3_ID), Some(&false));
+        assert_eq!(data.payers.get(CUSTOMER1_ID), Some(&CUSTOMER1_ID));
+
+        // Checks on the contract asset
+        let asset: Asset = not_wasm::get_account_asset_gen(PAY_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 0);
+
+        // Only the uncollected share is refunded
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER1_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 0);
+
+        let asset: Asset = not_wasm::get_account_asset_gen(CUSTOMER2_ID, ASSET_ID);
+
+        assert_eq!(asset.units, 30);
+    }
+
+    #[test]
+    fn test_cancel_insufficient_balance() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, RESTAURATEUR_ID);
+
+        // Prepare the account data/config
+        let mut data = create_init_args();
+        *data.customers.get_mut(CUSTOMER1_ID).unwrap() = true;
+
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        // Associate a mock transfer to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+
+        // Associate a mock asset_lock to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        // Associate a mock balance to the asset account
+        not_wasm::set_contract_method(ASSET_ID, "balance", not_wasm::asset_balance);
+
+        let args = PackedValue::default();
+
+        let err = not_wasm::call_wrap(cancel, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "insufficient balance");
+    }
+
+    #[test]
+    fn test_cancel_not_authorized() {
+        // Prepare the environment
+        // Prepare the context
+        let ctx = not_wasm::create_app_context(PAY_ID, CUSTOMER1_ID);
+
+        // Prepare the account data/config
+        let data = create_init_args();
+        let data = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(PAY_ID, "config", &data);
+
+        let args = PackedValue::default();
+
+        let err = not_wasm::call_wrap(cancel, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
 }
@@ -17,7 +17,7 @@
 
 //! pay-meal tutorial contract types
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -29,17 +29,84 @@ pub struct InitArgs<'a> {
     pub asset: &'a str,                     // is the asset account
     pub part: u64,                          // is the the share for each diner
     pub customers: BTreeMap<&'a str, bool>, // the diners list
-    pub status: &'a str,                    // status of the contract: "open", "closed"
+    pub status: &'a str,                    // status of the contract: "open", "close", "cancelled"
+    #[serde(default)]
+    pub payers: BTreeMap<&'a str, &'a str>, // the account that paid each customer share
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub collected: BTreeSet<&'a str>, // the customers whose share has been collected
+}
+
+// Pay For Args
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct PayForArgs<'a> {
+    pub customer: &'a str, // the customer whose share is paid
+}
+
+// Public Summary
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct Summary<'a> {
+    pub asset: &'a str,  // is the asset account
+    pub part: u64,       // is the the share for each diner
+    pub customers: u64,  // number of diners
+    pub paid: u64,       // number of diners whose share has been paid
+    pub total: u64,      // the whole bill amount
+    pub status: &'a str, // status of the contract: "open", "close", "cancelled"
+}
+
+// Customer Info
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct CustomerInfo<'a> {
+    pub restaurateur: &'a str,  // is the merchant account
+    pub customer: &'a str,      // is the caller account
+    pub paid: bool,             // `true` when the caller share has been paid
+    pub payer: Option<&'a str>, // the account that paid the caller share
+    pub summary: Summary<'a>,   // aggregate state of the contract
+}
+
+// Customer Paid Event
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct PaidEvent<'a> {
+    pub customer: &'a str, // the customer whose share has been paid
+    pub payer: &'a str,    // the account that paid the share
+    pub units: u64,        // the paid amount
+}
+
+// Bill Closed Event
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct ClosedEvent<'a> {
+    pub restaurateur: &'a str, // the account receiving the amount
+    pub amount: u64,           // the amount swept to the restaurateur
+    pub status: &'a str,       // status of the contract after the close
+}
+
+// Refund Issued Event
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct RefundEvent<'a> {
+    pub customer: &'a str, // the customer whose share has been refunded
+    pub payer: &'a str,    // the account receiving the refund
+    pub units: u64,        // the refunded amount
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
 
-    use std::collections::BTreeMap;
+    use std::collections::{BTreeMap, BTreeSet};
 
     use super::*;
 
-    const INIT_ARGS_HEX: &str = "95d92e516d5265737461757261746575725438696a5737524564334b714e316b474267597846577378616a6a67754c6b79d92e516d41737365745438696a73646657736437524566333564334b714e316b474267597846577378616a6a67754c6b1e83d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2d92e516d437573746f6d6572322d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2d92e516d437573746f6d6572332d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2a46f70656e";
+    const INIT_ARGS_HEX: &str = "96d92e516d5265737461757261746575725438696a5737524564334b714e316b474267597846577378616a6a67754c6b79d92e516d41737365745438696a73646657736437524566333564334b714e316b474267597846577378616a6a67754c6b1e83d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2d92e516d437573746f6d6572322d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2d92e516d437573746f6d6572332d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc2a46f70656e80";
+    const PAY_FOR_ARGS_HEX: &str = "91d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6b";
+    const SUMMARY_HEX: &str = "96d92e516d41737365745438696a73646657736437524566333564334b714e316b474267597846577378616a6a67754c6b1e03015aa46f70656e";
+    const CUSTOMER_INFO_HEX: &str = "95d92e516d5265737461757261746575725438696a5737524564334b714e316b474267597846577378616a6a67754c6b79d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bc3d92e516d437573746f6d6572322d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6b96d92e516d41737365745438696a73646657736437524566333564334b714e316b474267597846577378616a6a67754c6b1e03015aa46f70656e";
+    const PAID_EVENT_HEX: &str = "93d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bd92e516d437573746f6d6572322d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6b1e";
+    const CLOSED_EVENT_HEX: &str = "93d92e516d5265737461757261746575725438696a5737524564334b714e316b474267597846577378616a6a67754c6b795aa5636c6f7365";
+    const REFUND_EVENT_HEX: &str = "93d92e516d437573746f6d6572312d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6bd92e516d437573746f6d6572322d5438696a7364665773333564334b714e316b474267597846577378616a6a67754c6b1e";
 
     pub(crate) const PAY_ID: &str = "QmContractd7RqEzdV3gKjqXN1kGBgYxFWsxajjguLkyy7";
     pub(crate) const RESTAURATEUR_ID: &str = "QmRestaurateurT8ijW7REd3KqN1kGBgYxFWsxajjguLky";
@@ -48,6 +115,27 @@ pub(crate) mod tests {
     pub(crate) const CUSTOMER2_ID: &str = "QmCustomer2-T8ijsdfWs35d3KqN1kGBgYxFWsxajjguLk";
     pub(crate) const CUSTOMER3_ID: &str = "QmCustomer3-T8ijsdfWs35d3KqN1kGBgYxFWsxajjguLk";
 
+    pub(crate) fn create_summary() -> Summary<'static> {
+        Summary {
+            asset: ASSET_ID,
+            part: 30,
+            customers: 3,
+            paid: 1,
+            total: 90,
+            status: "open",
+        }
+    }
+
+    pub(crate) fn create_customer_info() -> CustomerInfo<'static> {
+        CustomerInfo {
+            restaurateur: RESTAURATEUR_ID,
+            customer: CUSTOMER1_ID,
+            paid: true,
+            payer: Some(CUSTOMER2_ID),
+            summary: create_summary(),
+        }
+    }
+
     pub(crate) fn create_init_args() -> InitArgs<'static> {
         let mut customers = BTreeMap::new();
         customers.insert(CUSTOMER3_ID, false);
@@ -60,6 +148,8 @@ pub(crate) mod tests {
             part: 30,
             customers,
             status: "open",
+            payers: BTreeMap::new(),
+            collected: BTreeSet::new(),
         }
     }
 
@@ -82,4 +172,152 @@ pub(crate) mod tests {
 
         assert_eq!(args, expected);
     }
+
+    #[test]
+    fn pay_for_args_serialize() {
+        let args = PayForArgs {
+            customer: CUSTOMER1_ID,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), PAY_FOR_ARGS_HEX);
+    }
+
+    #[test]
+    fn pay_for_args_deserialize() {
+        let expected = PayForArgs {
+            customer: CUSTOMER1_ID,
+        };
+
+        let buf = hex::decode(PAY_FOR_ARGS_HEX).unwrap();
+
+        let args: PayForArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn summary_serialize() {
+        let summary = create_summary();
+
+        let buf = trinci_sdk::rmp_serialize(&summary).unwrap();
+
+        assert_eq!(hex::encode(&buf), SUMMARY_HEX);
+    }
+
+    #[test]
+    fn summary_deserialize() {
+        let expected = create_summary();
+
+        let buf = hex::decode(SUMMARY_HEX).unwrap();
+
+        let summary: Summary = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(summary, expected);
+    }
+
+    #[test]
+    fn customer_info_serialize() {
+        let info = create_customer_info();
+
+        let buf = trinci_sdk::rmp_serialize(&info).unwrap();
+
+        assert_eq!(hex::encode(&buf), CUSTOMER_INFO_HEX);
+    }
+
+    #[test]
+    fn customer_info_deserialize() {
+        let expected = create_customer_info();
+
+        let buf = hex::decode(CUSTOMER_INFO_HEX).unwrap();
+
+        let info: CustomerInfo = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(info, expected);
+    }
+
+    #[test]
+    fn paid_event_serialize() {
+        let event = PaidEvent {
+            customer: CUSTOMER1_ID,
+            payer: CUSTOMER2_ID,
+            units: 30,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&event).unwrap();
+
+        assert_eq!(hex::encode(&buf), PAID_EVENT_HEX);
+    }
+
+    #[test]
+    fn paid_event_deserialize() {
+        let expected = PaidEvent {
+            customer: CUSTOMER1_ID,
+            payer: CUSTOMER2_ID,
+            units: 30,
+        };
+
+        let buf = hex::decode(PAID_EVENT_HEX).unwrap();
+
+        let event: PaidEvent = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(event, expected);
+    }
+
+    #[test]
+    fn closed_event_serialize() {
+        let event = ClosedEvent {
+            restaurateur: RESTAURATEUR_ID,
+            amount: 90,
+            status: "close",
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&event).unwrap();
+
+        assert_eq!(hex::encode(&buf), CLOSED_EVENT_HEX);
+    }
+
+    #[test]
+    fn closed_event_deserialize() {
+        let expected = ClosedEvent {
+            restaurateur: RESTAURATEUR_ID,
+            amount: 90,
+            status: "close",
+        };
+
+        let buf = hex::decode(CLOSED_EVENT_HEX).unwrap();
+
+        let event: ClosedEvent = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(event, expected);
+    }
+
+    #[test]
+    fn refund_event_serialize() {
+        let event = RefundEvent {
+            customer: CUSTOMER1_ID,
+            payer: CUSTOMER2_ID,
+            units: 30,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&event).unwrap();
+
+        assert_eq!(hex::encode(&buf), REFUND_EVENT_HEX);
+    }
+
+    #[test]
+    fn refund_event_deserialize() {
+        let expected = RefundEvent {
+            customer: CUSTOMER1_ID,
+            payer: CUSTOMER2_ID,
+            units: 30,
+        };
+
+        let buf = hex::decode(REFUND_EVENT_HEX).unwrap();
+
+        let event: RefundEvent = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(event, expected);
+    }
 }
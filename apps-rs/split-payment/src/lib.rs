@@ -0,0 +1,530 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+
+//! Split Payment
+//!
+//! Generalization of the pay-meal tutorial contract.
+//! The account holds many concurrent bills, each one identified by an id
+//! and split in equal shares between a list of participants.
+//! The paid shares are kept in escrow on the account until the merchant
+//! collects them or cancels the bill.
+//!
+//! ### Methods
+//!
+//!  - `open` - can be called only from the account owner
+//!  - `apply` - can be called only by a bill participant
+//!  - `pay_for` - can be called by anyone to pay the share of a participant
+//!  - `get_info` - can be called only by a bill participant or the merchant
+//!  - `close` - can be called only by the merchant,
+//!    transfer the collected shares to the merchant
+//!    close the bill if all the participants have been paid
+//!  - `cancel` - can be called only by the merchant,
+//!    refund every paid share still in escrow to the account that paid it
+//!    and cancel the bill
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use trinci_sdk::{
+    rmp_deserialize, rmp_serialize_named, tai::LockType, AppContext, PackedValue, WasmError,
+    WasmResult,
+};
+
+mod types;
+use types::*;
+
+trinci_sdk::app_export!(open, apply, pay_for, get_info, close, cancel);
+
+const BILLS_PREFIX: &str = "bills:";
+
+#[inline]
+fn bill_key(id: &str) -> String {
+    format!("{}{}", BILLS_PREFIX, id)
+}
+
+/// Deserialize a bill loaded from the account data.
+fn load_bill(buf: &[u8]) -> WasmResult<Bill<'_>> {
+    rmp_deserialize(buf).map_err(|_| WasmError::new("bill not found"))
+}
+
+/// Deserialize a bill and check that it is still open.
+fn load_open_bill(buf: &[u8]) -> WasmResult<Bill<'_>> {
+    let bill = load_bill(buf)?;
+    if bill.status != "open" {
+        return Err(WasmError::new("bill closed"));
+    }
+    Ok(bill)
+}
+
+/// Transfer some units of the escrowed asset out of the contract account.
+///
+/// The asset is unlocked only for the time of the transfer.
+fn release(owner: &str, asset: &str, to: &str, units: u64) -> WasmResult<()> {
+    trinci_sdk::asset_lock(asset, owner, LockType::None)?;
+    trinci_sdk::asset_transfer(owner, to, asset, units)?;
+    trinci_sdk::asset_lock(asset, owner, LockType::Withdraw)
+}
+
+/// Open method
+fn open(ctx: AppContext, args: OpenArgs) -> WasmResult<()> {
+    if ctx.owner != ctx.caller {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    if args.id.is_empty() || args.participants.is_empty() {
+        return Err(WasmError::new("invalid arguments"));
+    }
+
+    let key = bill_key(args.id);
+    if !trinci_sdk::load_data(&key).is_empty() {
+        return Err(WasmError::new("bill already exists"));
+    }
+
+    let bill = Bill {
+        merchant: args.merchant,
+        asset: args.asset,
+        part: args.part,
+        participants: args.participants.iter().map(|&p| (p, false)).collect(),
+        payers: BTreeMap::new(),
+        collected: 0,
+        status: "open",
+        released: BTreeSet::new(),
+    };
+
+    // Prevent to withdraw the asset
+    trinci_sdk::asset_lock(bill.asset, ctx.owner, LockType::Withdraw)?;
+
+    trinci_sdk::store_account_data_mp!(&key, &bill)
+}
+
+/// Get_Info method
+fn get_info(ctx: AppContext, args: BillArgs) -> WasmResult<PackedValue> {
+    let buf = trinci_sdk::load_data(&bill_key(args.id));
+    let bill = load_bill(&buf)?;
+
+    if ctx.caller != bill.merchant && !bill.participants.contains_key(ctx.caller) {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let buf = rmp_serialize_named(&bill)?;
+    Ok(PackedValue(buf))
+}
+
+/// Pay the `participant` share taking the asset from the `payer` account
+fn pay(ctx: &AppContext, id: &str, payer: &str, participant: &str) -> WasmResult<()> {
+    let key = bill_key(id);
+    let buf = trinci_sdk::load_data(&key);
+    let mut bill = load_open_bill(&buf)?;
+
+    match bill.participants.get_mut(participant) {
+        Some(paid) if !*paid => {
+            trinci_sdk::asset_transfer(payer, ctx.owner, bill.asset, bill.part)
+                .map_err(|_| WasmError::new("transfer from caller failed"))?;
+            *paid = true;
+        }
+        Some(_) => return Err(WasmError::new("already paid")),
+        None => return Err(WasmError::new("not authorized")),
+    }
+
+    bill.payers.insert(participant, payer);
+    bill.collected += bill.part;
+
+    trinci_sdk::store_account_data_mp!(&key, &bill)
+}
+
+/// Apply method
+fn apply(ctx: AppContext, args: BillArgs) -> WasmResult<()> {
+    pay(&ctx, args.id, ctx.caller, ctx.caller)
+}
+
+/// Pay_For method
+fn pay_for(ctx: AppContext, args: PayForArgs) -> WasmResult<()> {
+    pay(&ctx, args.id, ctx.caller, args.participant)
+}
+
+/// Close method
+fn close(ctx: AppContext, args: BillArgs) -> WasmResult<()> {
+    let key = bill_key(args.id);
+    let buf = trinci_sdk::load_data(&key);
+    let mut bill = load_open_bill(&buf)?;
+
+    if ctx.caller != bill.merchant {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    // Only the shares of this bill are transferred, the account can hold
+    // the escrow of other bills in the same asset.
+    if bill.collected > 0 {
+        release(ctx.owner, bill.asset, bill.merchant, bill.collected)?;
+        bill.collected = 0;
+    }
+
+    // Record the shares transferred, they can no longer be refunded
+    for (&participant, _) in bill.participants.iter().filter(|(_, paid)| **paid) {
+        bill.released.insert(participant);
+    }
+
+    if bill.participants.values().all(|&paid| paid) {
+        bill.status = "close";
+    }
+
+    trinci_sdk::store_account_data_mp!(&key, &bill)
+}
+
+/// Cancel method
+fn cancel(ctx: AppContext, args: BillArgs) -> WasmResult<()> {
+    let key = bill_key(args.id);
+    let buf = trinci_sdk::load_data(&key);
+    let mut bill = load_open_bill(&buf)?;
+
+    if ctx.caller != bill.merchant {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    // Refund the shares still in escrow, the ones already transferred to
+    // the merchant stay with the merchant.
+    if bill.collected > 0 {
+        let released = &bill.released;
+        trinci_sdk::asset_lock(bill.asset, ctx.owner, LockType::None)?;
+        for (&participant, paid) in bill
+            .participants
+            .iter_mut()
+            .filter(|(participant, paid)| **paid && !released.contains(*participant))
+        {
+            let payer = bill.payers.remove(participant).unwrap_or(participant);
+            trinci_sdk::asset_transfer(ctx.owner, payer, bill.asset, bill.part)?;
+            *paid = false;
+        }
+        trinci_sdk::asset_lock(bill.asset, ctx.owner, LockType::Withdraw)?;
+        bill.collected = 0;
+    }
+
+    bill.status = "cancelled";
+    trinci_sdk::store_account_data_mp!(&key, &bill)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use crate::types::tests::{
+        create_bill, ASSET_ID, BILL_ID, MERCHANT_ID, PARTICIPANT1_ID, PARTICIPANT2_ID, SPLIT_ID,
+    };
+    use trinci_sdk::{not_wasm, rmp_serialize, tai::Asset, value, Value};
+
+    fn prepare_env(bill: &Bill) {
+        let data = rmp_serialize(bill).unwrap();
+        not_wasm::set_account_data(SPLIT_ID, &bill_key(BILL_ID), &data);
+
+        not_wasm::set_contract_method(ASSET_ID, "transfer", not_wasm::asset_transfer);
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+        not_wasm::set_contract_method(ASSET_ID, "balance", not_wasm::asset_balance);
+    }
+
+    fn stored_bill(id: &str) -> Vec<u8> {
+        not_wasm::get_account_data(SPLIT_ID, &bill_key(id))
+    }
+
+    fn create_open_args(id: &'static str) -> OpenArgs<'static> {
+        OpenArgs {
+            id,
+            merchant: MERCHANT_ID,
+            asset: ASSET_ID,
+            part: 30,
+            participants: vec![PARTICIPANT1_ID, PARTICIPANT2_ID],
+        }
+    }
+
+    #[test]
+    fn test_open() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, SPLIT_ID);
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        not_wasm::call_wrap(open, ctx, create_open_args(BILL_ID)).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        assert_eq!(load_bill(&buf).unwrap(), create_bill());
+    }
+
+    #[test]
+    fn test_open_many_bills() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, SPLIT_ID);
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        not_wasm::call_wrap(open, ctx, create_open_args("lunch")).unwrap();
+        not_wasm::call_wrap(open, ctx, create_open_args("dinner")).unwrap();
+
+        let buf = stored_bill("lunch");
+        assert_eq!(load_bill(&buf).unwrap(), create_bill());
+        let buf = stored_bill("dinner");
+        assert_eq!(load_bill(&buf).unwrap(), create_bill());
+    }
+
+    #[test]
+    fn test_open_already_existing() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, SPLIT_ID);
+        prepare_env(&create_bill());
+
+        let err = not_wasm::call_wrap(open, ctx, create_open_args(BILL_ID)).unwrap_err();
+
+        assert_eq!(err.to_string(), "bill already exists");
+    }
+
+    #[test]
+    fn test_open_not_authorized() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        not_wasm::set_contract_method(ASSET_ID, "lock", not_wasm::asset_lock);
+
+        let err = not_wasm::call_wrap(open, ctx, create_open_args(BILL_ID)).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn test_get_info() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        prepare_env(&create_bill());
+
+        let res = not_wasm::call_wrap(get_info, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let data: Value = rmp_deserialize(&res.0).unwrap();
+        let merchant = data.get(&value!("merchant")).unwrap().as_str().unwrap();
+        assert_eq!(merchant, MERCHANT_ID);
+    }
+
+    #[test]
+    fn test_get_info_not_found() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+
+        let err = not_wasm::call_wrap(get_info, ctx, BillArgs { id: "unknown" }).unwrap_err();
+
+        assert_eq!(err.to_string(), "bill not found");
+    }
+
+    #[test]
+    fn test_get_info_not_authorized() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, "unknown");
+        prepare_env(&create_bill());
+
+        let err = not_wasm::call_wrap(get_info, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn test_apply() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        prepare_env(&create_bill());
+        not_wasm::set_account_asset_gen(PARTICIPANT1_ID, ASSET_ID, Asset::new(100));
+
+        not_wasm::call_wrap(apply, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert!(bill.participants.get(PARTICIPANT1_ID).unwrap());
+        assert_eq!(bill.payers.get(PARTICIPANT1_ID), Some(&PARTICIPANT1_ID));
+        assert_eq!(bill.collected, 30);
+        let asset: Asset = not_wasm::get_account_asset_gen(SPLIT_ID, ASSET_ID);
+        assert_eq!(asset.units, 30);
+    }
+
+    #[test]
+    fn test_apply_on_already_paid() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        let mut bill = create_bill();
+        *bill.participants.get_mut(PARTICIPANT1_ID).unwrap() = true;
+        prepare_env(&bill);
+        not_wasm::set_account_asset_gen(PARTICIPANT1_ID, ASSET_ID, Asset::new(100));
+
+        let err = not_wasm::call_wrap(apply, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "already paid");
+    }
+
+    #[test]
+    fn test_apply_on_closed_bill() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        let mut bill = create_bill();
+        bill.status = "close";
+        prepare_env(&bill);
+        not_wasm::set_account_asset_gen(PARTICIPANT1_ID, ASSET_ID, Asset::new(100));
+
+        let err = not_wasm::call_wrap(apply, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "bill closed");
+    }
+
+    #[test]
+    fn test_apply_not_authorized() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, "unknown");
+        prepare_env(&create_bill());
+        not_wasm::set_account_asset_gen("unknown", ASSET_ID, Asset::new(100));
+
+        let err = not_wasm::call_wrap(apply, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn test_apply_without_funds() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        prepare_env(&create_bill());
+
+        let err = not_wasm::call_wrap(apply, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "transfer from caller failed");
+    }
+
+    #[test]
+    fn test_pay_for() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        prepare_env(&create_bill());
+        not_wasm::set_account_asset_gen(PARTICIPANT1_ID, ASSET_ID, Asset::new(100));
+        let args = PayForArgs {
+            id: BILL_ID,
+            participant: PARTICIPANT2_ID,
+        };
+
+        not_wasm::call_wrap(pay_for, ctx, args).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert!(bill.participants.get(PARTICIPANT2_ID).unwrap());
+        assert!(!bill.participants.get(PARTICIPANT1_ID).unwrap());
+        assert_eq!(bill.payers.get(PARTICIPANT2_ID), Some(&PARTICIPANT1_ID));
+        let asset: Asset = not_wasm::get_account_asset_gen(PARTICIPANT1_ID, ASSET_ID);
+        assert_eq!(asset.units, 70);
+    }
+
+    #[test]
+    fn test_close() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, MERCHANT_ID);
+        let mut bill = create_bill();
+        *bill.participants.get_mut(PARTICIPANT1_ID).unwrap() = true;
+        *bill.participants.get_mut(PARTICIPANT2_ID).unwrap() = true;
+        bill.collected = 60;
+        prepare_env(&bill);
+        not_wasm::set_account_asset_gen(SPLIT_ID, ASSET_ID, Asset::new(60));
+
+        not_wasm::call_wrap(close, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert_eq!(bill.status, "close");
+        assert_eq!(bill.collected, 0);
+        let asset: Asset = not_wasm::get_account_asset_gen(SPLIT_ID, ASSET_ID);
+        assert_eq!(asset.units, 0);
+        let asset: Asset = not_wasm::get_account_asset_gen(MERCHANT_ID, ASSET_ID);
+        assert_eq!(asset.units, 60);
+    }
+
+    #[test]
+    fn test_close_leaves_other_bills_escrow() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, MERCHANT_ID);
+        let mut bill = create_bill();
+        *bill.participants.get_mut(PARTICIPANT1_ID).unwrap() = true;
+        bill.collected = 30;
+        prepare_env(&bill);
+        // Another bill holds 50 units in the same asset
+        not_wasm::set_account_asset_gen(SPLIT_ID, ASSET_ID, Asset::new(80));
+
+        not_wasm::call_wrap(close, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert_eq!(bill.status, "open");
+        assert_eq!(bill.collected, 0);
+        assert!(bill.released.contains(PARTICIPANT1_ID));
+        assert!(!bill.released.contains(PARTICIPANT2_ID));
+        let asset: Asset = not_wasm::get_account_asset_gen(SPLIT_ID, ASSET_ID);
+        assert_eq!(asset.units, 50);
+        let asset: Asset = not_wasm::get_account_asset_gen(MERCHANT_ID, ASSET_ID);
+        assert_eq!(asset.units, 30);
+    }
+
+    #[test]
+    fn test_close_not_authorized() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT1_ID);
+        prepare_env(&create_bill());
+
+        let err = not_wasm::call_wrap(close, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn test_cancel() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, MERCHANT_ID);
+        let mut bill = create_bill();
+        *bill.participants.get_mut(PARTICIPANT2_ID).unwrap() = true;
+        bill.payers.insert(PARTICIPANT2_ID, PARTICIPANT1_ID);
+        bill.collected = 30;
+        prepare_env(&bill);
+        not_wasm::set_account_asset_gen(SPLIT_ID, ASSET_ID, Asset::new(30));
+
+        not_wasm::call_wrap(cancel, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert_eq!(bill.status, "cancelled");
+        assert_eq!(bill.collected, 0);
+        assert!(bill.payers.is_empty());
+        let asset: Asset = not_wasm::get_account_asset_gen(PARTICIPANT1_ID, ASSET_ID);
+        assert_eq!(asset.units, 30);
+        let asset: Asset = not_wasm::get_account_asset_gen(PARTICIPANT2_ID, ASSET_ID);
+        assert_eq!(asset.units, 0);
+    }
+
+    #[test]
+    fn test_cancel_with_collected_shares() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, MERCHANT_ID);
+        // Participant1 share has been transferred to the merchant,
+        // Participant2 paid later and its share is still in escrow
+        let mut bill = create_bill();
+        *bill.participants.get_mut(PARTICIPANT1_ID).unwrap() = true;
+        *bill.participants.get_mut(PARTICIPANT2_ID).unwrap() = true;
+        bill.payers.insert(PARTICIPANT1_ID, PARTICIPANT1_ID);
+        bill.payers.insert(PARTICIPANT2_ID, PARTICIPANT2_ID);
+        bill.released.insert(PARTICIPANT1_ID);
+        bill.collected = 30;
+        prepare_env(&bill);
+        not_wasm::set_account_asset_gen(SPLIT_ID, ASSET_ID, Asset::new(30));
+
+        not_wasm::call_wrap(cancel, ctx, BillArgs { id: BILL_ID }).unwrap();
+
+        let buf = stored_bill(BILL_ID);
+        let bill = load_bill(&buf).unwrap();
+        assert_eq!(bill.status, "cancelled");
+        assert_eq!(bill.collected, 0);
+        assert!(bill.participants.get(PARTICIPANT1_ID).unwrap());
+        assert!(!bill.participants.get(PARTICIPANT2_ID).unwrap());
+        let asset: Asset = not_wasm::get_account_asset_gen(PARTICIPANT1_ID, ASSET_ID);
+        assert_eq!(asset.units, 0);
+        let asset: Asset = not_wasm::get_account_asset_gen(PARTICIPANT2_ID, ASSET_ID);
+        assert_eq!(asset.units, 30);
+    }
+
+    #[test]
+    fn test_cancel_not_authorized() {
+        let ctx = not_wasm::create_app_context(SPLIT_ID, PARTICIPANT2_ID);
+        prepare_env(&create_bill());
+
+        let err = not_wasm::call_wrap(cancel, ctx, BillArgs { id: BILL_ID }).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+}
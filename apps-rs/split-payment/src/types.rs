@@ -0,0 +1,228 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+
+//! split-payment contract types
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
+
+/// Open method arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct OpenArgs<'a> {
+    /// Bill identifier.
+    pub id: &'a str,
+    /// Account collecting the payments.
+    pub merchant: &'a str,
+    /// Asset used for the payments.
+    pub asset: &'a str,
+    /// Share to be paid by each participant.
+    pub part: u64,
+    /// Accounts sharing the bill.
+    pub participants: Vec<&'a str>,
+}
+
+/// Arguments of the methods working on a single bill.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BillArgs<'a> {
+    /// Bill identifier.
+    pub id: &'a str,
+}
+
+/// Pay for method arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct PayForArgs<'a> {
+    /// Bill identifier.
+    pub id: &'a str,
+    /// Participant whose share is paid.
+    pub participant: &'a str,
+}
+
+/// Bill status stored in the account data.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Bill<'a> {
+    /// Account collecting the payments.
+    pub merchant: &'a str,
+    /// Asset used for the payments.
+    pub asset: &'a str,
+    /// Share to be paid by each participant.
+    pub part: u64,
+    /// Participants list, `true` when the share has been paid.
+    pub participants: BTreeMap<&'a str, bool>,
+    /// Account that paid each participant share.
+    pub payers: BTreeMap<&'a str, &'a str>,
+    /// Units paid and not yet transferred to the merchant (dynamic).
+    pub collected: u64,
+    /// Bill status: "open", "close", "cancelled".
+    pub status: &'a str,
+    /// Participants whose share has been transferred to the merchant.
+    #[serde(default)]
+    pub released: BTreeSet<&'a str>,
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+
+    use super::*;
+    use trinci_sdk::{rmp_deserialize, rmp_serialize};
+
+    pub(crate) const SPLIT_ID: &str = "QmSplitPaymentdV3gKjqXN1kGBgYxFWsxajjguLkyy7";
+    pub(crate) const MERCHANT_ID: &str = "QmMerchantT8ijW7REd3KqN1kGBgYxFWsxajjguLkyAbc";
+    pub(crate) const ASSET_ID: &str = "QmAssetT8ijsdfWsd7REf35d3KqN1kGBgYxFWsxajjguLk";
+    pub(crate) const PARTICIPANT1_ID: &str = "QmParticipant1-T8ijsdfWs35d3KqN1kGBgYxFWsxajj";
+    pub(crate) const PARTICIPANT2_ID: &str = "QmParticipant2-T8ijsdfWs35d3KqN1kGBgYxFWsxajj";
+    pub(crate) const BILL_ID: &str = "dinner";
+
+    const OPEN_ARGS_HEX: &str = "95a664696e6e6572a34d4552a3464b430a92a150a151";
+    const BILL_ARGS_HEX: &str = "91a664696e6e6572";
+    const PAY_FOR_ARGS_HEX: &str = "92a664696e6e6572a150";
+    const BILL_HEX: &str = "98a34d4552a3464b430a82a150c3a151c281a150a1510aa46f70656e90";
+
+    pub(crate) fn create_bill() -> Bill<'static> {
+        let mut participants = BTreeMap::new();
+        participants.insert(PARTICIPANT1_ID, false);
+        participants.insert(PARTICIPANT2_ID, false);
+
+        Bill {
+            merchant: MERCHANT_ID,
+            asset: ASSET_ID,
+            part: 30,
+            participants,
+            payers: BTreeMap::new(),
+            collected: 0,
+            status: "open",
+            released: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn open_args_serialize() {
+        let args = OpenArgs {
+            id: BILL_ID,
+            merchant: "MER",
+            asset: "FKC",
+            part: 10,
+            participants: vec!["P", "Q"],
+        };
+
+        let buf = rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), OPEN_ARGS_HEX);
+    }
+
+    #[test]
+    fn open_args_deserialize() {
+        let expected = OpenArgs {
+            id: BILL_ID,
+            merchant: "MER",
+            asset: "FKC",
+            part: 10,
+            participants: vec!["P", "Q"],
+        };
+        let buf = hex::decode(OPEN_ARGS_HEX).unwrap();
+
+        let args: OpenArgs = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn bill_args_serialize() {
+        let args = BillArgs { id: BILL_ID };
+
+        let buf = rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), BILL_ARGS_HEX);
+    }
+
+    #[test]
+    fn bill_args_deserialize() {
+        let expected = BillArgs { id: BILL_ID };
+        let buf = hex::decode(BILL_ARGS_HEX).unwrap();
+
+        let args: BillArgs = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn pay_for_args_serialize() {
+        let args = PayForArgs {
+            id: BILL_ID,
+            participant: "P",
+        };
+
+        let buf = rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), PAY_FOR_ARGS_HEX);
+    }
+
+    #[test]
+    fn pay_for_args_deserialize() {
+        let expected = PayForArgs {
+            id: BILL_ID,
+            participant: "P",
+        };
+        let buf = hex::decode(PAY_FOR_ARGS_HEX).unwrap();
+
+        let args: PayForArgs = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_small_bill() -> Bill<'static> {
+        let mut participants = BTreeMap::new();
+        participants.insert("P", true);
+        participants.insert("Q", false);
+        let mut payers = BTreeMap::new();
+        payers.insert("P", "Q");
+
+        Bill {
+            merchant: "MER",
+            asset: "FKC",
+            part: 10,
+            participants,
+            payers,
+            collected: 10,
+            status: "open",
+            released: BTreeSet::new(),
+        }
+    }
+
+    #[test]
+    fn bill_serialize() {
+        let bill = create_small_bill();
+
+        let buf = rmp_serialize(&bill).unwrap();
+
+        assert_eq!(hex::encode(&buf), BILL_HEX);
+    }
+
+    #[test]
+    fn bill_deserialize() {
+        let expected = create_small_bill();
+        let buf = hex::decode(BILL_HEX).unwrap();
+
+        let bill: Bill = rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(bill, expected);
+    }
+}
@@ -26,162 +26,3386 @@
 //! ### Rules
 //!
 //! 1. Only the account owner is allowed to invoke the methods.
+//! 2. The owner can grant to other accounts (users or contracts) the
+//!    permission to read, write or delete a key or all the keys starting
+//!    with a prefix. The grants are checked by `load_data`, `store_data`
+//!    and `remove_data`.
+//! 3. The access control lists are stored under the reserved `__sys/acl:`
+//!    prefix and can be modified only via `grant` and `revoke`.
+//! 4. `list_keys` never returns the reserved keys and returns to the other
+//!    accounts only the keys they are allowed to read.
+//! 5. Every stored value carries a version, incremented by each store or
+//!    remove of the key. The versions are stored under the reserved
+//!    `__sys/ver:` prefix.
+//! 6. A value can be handled as a msgpack document with `get_field`,
+//!    `set_field`, `delete_field` and `append`, addressing a field by the
+//!    path of map fields and array indexes leading to it. The field
+//!    methods follow the grants of `load_data` and `store_data`.
+//! 7. The owner can upload blobs in chunks with `begin_upload`, `put_chunk`
+//!    and `commit_upload`. The blobs, up to 1 MiB, are verified against
+//!    their sha256, stored once under the reserved `__sys/blob:` prefix
+//!    and addressed by the hash. The pending uploads are stored under the
+//!    reserved `__sys/upload:` prefix.
+//! 8. The owner can set a quota on the number of data keys, on the size of
+//!    each value and on the size of all the values. The quota is checked
+//!    by every write that increases the usage, including the blobs, the
+//!    uploads, the notarizations, the orders, the operators and the spend
+//!    log. Only the versions, the ACLs and the configuration are not
+//!    accounted.
+//! 9. The protected keys (by default `config` and `init`, used by the
+//!    asset contracts) can be modified only by the owner through
+//!    `store_data` and `remove_data` with the `force` flag set.
+//! 10. The quota, the usage and the protected keys are stored under the
+//!     reserved `__sys/cfg:` prefix.
+//! 11. The owner and the accounts granted to write under the reserved
+//!     `__sys/notary:` prefix can `notarize` a sha256 digest, the record is
+//!     stored under that prefix and can never be overwritten or removed,
+//!     not even by the owner. Anyone can `verify` a digest.
+//! 12. The owner can register standing orders, transferring an asset once
+//!     or periodically from a given block time. Any account can call
+//!     `execute_due` to execute the due orders, receiving the order reward.
+//!     Each due period is paid once, the orders are stored under the
+//!     reserved `__sys/order:` prefix.
+//! 13. The owner can authorize operators to `transfer` from the account,
+//!     within a spending limit per asset and period and only to the allowed
+//!     destinations. A limit with a zero period is a lifetime limit. The
+//!     operators are stored under the reserved `__sys/operator:` prefix,
+//!     every operator spend is logged under the reserved `__sys/spend:`
+//!     prefix.
+//! 14. A value can hold an envelope of data encrypted by the client, with
+//!     the content key wrapped for each recipient. The owner can add or
+//!     revoke the recipients of an envelope with `add_recipient` and
+//!     `revoke_recipient`, leaving the ciphertext untouched: the wrapped
+//!     keys are stored under the reserved `__sys/wrap:` prefix and are
+//!     removed together with the value.
+//! 15. All the reserved keys live in the `__sys/` namespace. Before 0.2.0
+//!     the contract had no reserved keys: when upgrading, the user keys
+//!     starting with `__sys/` become readable only by the owner and can no
+//!     longer be modified, move them to another key before the upgrade.
 
-use trinci_sdk::{AppContext, PackedValue, WasmError, WasmResult};
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::Serialize;
+use serde_bytes::ByteBuf;
+use trinci_sdk::{
+    rmp_deserialize, rmp_serialize, rmp_serialize_named, AppContext, PackedValue, Value, WasmError,
+    WasmResult,
+};
 
 mod types;
 use types::*;
 
-trinci_sdk::app_export!(init, load_data, store_data, remove_data, balance, transfer);
+trinci_sdk::app_export!(
+    init,
+    load_data,
+    store_data,
+    remove_data,
+    load_data_versioned,
+    store_data_if_version,
+    list_keys,
+    load_many,
+    batch,
+    get_field,
+    set_field,
+    delete_field,
+    append,
+    store_envelope,
+    load_envelope,
+    add_recipient,
+    revoke_recipient,
+    begin_upload,
+    put_chunk,
+    commit_upload,
+    blob_info,
+    load_chunk,
+    notarize,
+    verify,
+    set_quota,
+    get_quota,
+    set_protected,
+    get_protected,
+    grant,
+    revoke,
+    get_acl,
+    balance,
+    balances,
+    transfer,
+    multi_transfer,
+    add_order,
+    remove_order,
+    get_order,
+    execute_due,
+    set_operator,
+    revoke_operator,
+    get_operator,
+    get_spends
+);
+
+/// Reserved namespace of the keys handled by the contract itself.
+const SYS_PREFIX: &str = "__sys/";
+
+/// Prefix of the keys holding the access control lists.
+const ACL_PREFIX: &str = "__sys/acl:";
+/// Prefix of the keys holding the values versions.
+const VERSION_PREFIX: &str = "__sys/ver:";
+
+/// Prefix of the keys holding the contract configuration.
+const CONFIG_PREFIX: &str = "__sys/cfg:";
+/// Key holding the account data quota.
+const QUOTA_KEY: &str = "__sys/cfg:quota";
+/// Key holding the account data usage.
+const USAGE_KEY: &str = "__sys/cfg:usage";
+/// Key holding the protected keys patterns.
+const PROTECTED_KEY: &str = "__sys/cfg:protected";
+/// Key holding the patterns having an access control list.
+const ACL_INDEX_KEY: &str = "__sys/cfg:acl";
+
+/// Prefix of the keys holding the notarization records.
+const NOTARY_PREFIX: &str = "__sys/notary:";
+/// Maximum length in bytes of the metadata of a notarization record.
+const MAX_NOTARY_METADATA: usize = 256;
+
+/// Prefix of the keys holding the standing orders.
+const ORDER_PREFIX: &str = "__sys/order:";
+/// Maximum number of orders executed by a single `execute_due` call.
+const MAX_DUE_ORDERS: usize = 20;
+
+/// Prefix of the keys holding the operators.
+const OPERATOR_PREFIX: &str = "__sys/operator:";
+/// Prefix of the keys holding the operators spend log.
+const SPEND_PREFIX: &str = "__sys/spend:";
+/// Key holding the number of records of the spend log.
+const SPEND_COUNT_KEY: &str = "__sys/cfg:spends";
+
+/// Keys protected when the owner has not set a list.
+const DEFAULT_PROTECTED: [&str; 2] = ["config", "init"];
+
+/// Prefix of the keys holding the pending uploads.
+const UPLOAD_PREFIX: &str = "__sys/upload:";
+/// Prefix of the keys holding the committed blobs.
+const BLOB_PREFIX: &str = "__sys/blob:";
+
+/// Size of the chunks the committed blobs are stored with.
+const BLOB_CHUNK_SIZE: u32 = 64 * 1024;
+/// Maximum size of a blob, committed as a whole.
+const MAX_BLOB_SIZE: u64 = 16 * BLOB_CHUNK_SIZE as u64;
+/// Maximum number of bytes returned by `load_chunk`.
+const MAX_RANGE_LENGTH: u32 = BLOB_CHUNK_SIZE;
+
+/// Prefix of the keys holding the wrapped content keys of the envelopes.
+const WRAP_PREFIX: &str = "__sys/wrap:";
+/// Maximum number of recipients of an envelope.
+const MAX_RECIPIENTS: usize = 32;
+
+/// Page size used by `list_keys` when no limit is given (and maximum limit).
+const MAX_LIST_LIMIT: u32 = 100;
+
+/// Batch operation storing the data.
+const BATCH_PUT: &str = "put";
+/// Batch operation removing the data.
+const BATCH_REMOVE: &str = "remove";
+
+/// Kind of access to an account data key.
+#[derive(Clone, Copy)]
+enum Access {
+    Read,
+    Write,
+    Delete,
+}
+
+/// Check if the key belongs to the reserved namespace.
+#[inline]
+fn is_reserved(key: &str) -> bool {
+    key.starts_with(SYS_PREFIX)
+}
+
+/// Check if `key` is matched by a key or a key prefix terminated by `*`.
+fn pattern_matches(pattern: &str, key: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => key.starts_with(prefix),
+        None => key == pattern,
+    }
+}
+
+#[inline]
+fn version_key(key: &str) -> String {
+    format!("{}{}", VERSION_PREFIX, key)
+}
+
+/// Current version of the value stored in `key` (0 if never written).
+fn load_version(key: &str) -> u64 {
+    let buf = trinci_sdk::load_data(&version_key(key));
+    rmp_deserialize(&buf).unwrap_or_default()
+}
+
+fn load_quota() -> Option<Quota> {
+    let buf = trinci_sdk::load_data(QUOTA_KEY);
+    rmp_deserialize(&buf).ok()
+}
+
+fn load_usage() -> Usage {
+    let buf = trinci_sdk::load_data(USAGE_KEY);
+    rmp_deserialize(&buf).unwrap_or_default()
+}
+
+/// Check if the size of the `key` value is accounted in the usage.
+///
+/// Only the contract bookkeeping (versions, ACLs and configuration) is not.
+fn is_accounted(key: &str) -> bool {
+    ![VERSION_PREFIX, ACL_PREFIX, CONFIG_PREFIX]
+        .iter()
+        .any(|prefix| key.starts_with(prefix))
+}
+
+/// Usage of the accounted account data keys.
+fn compute_usage() -> WasmResult<Usage> {
+    let mut usage = Usage::default();
+    for key in trinci_sdk::get_data_keys("*")? {
+        let size = trinci_sdk::load_data(&key).len() as u64;
+        if is_accounted(&key) && size > 0 {
+            usage.keys += 1;
+            usage.total_size += size;
+        }
+    }
+    Ok(usage)
+}
+
+/// Update the usage for the new size of the `key` value.
+///
+/// Fails if the quota is exceeded by a write increasing the usage, the
+/// usage is not tracked if the quota is not set.
+fn update_usage(key: &str, size: u64) -> WasmResult<()> {
+    let quota = match load_quota() {
+        Some(quota) => quota,
+        None => return Ok(()),
+    };
+    let old_size = trinci_sdk::load_data(key).len() as u64;
+    let old_usage = load_usage();
+    let usage = Usage {
+        keys: (old_usage.keys + (size > 0) as u64).saturating_sub((old_size > 0) as u64),
+        total_size: (old_usage.total_size + size).saturating_sub(old_size),
+    };
+
+    if quota.max_value_size != 0 && size > quota.max_value_size {
+        return Err(WasmError::new("value too large"));
+    }
+    if quota.max_keys != 0 && usage.keys > quota.max_keys && usage.keys > old_usage.keys {
+        return Err(WasmError::new("too many keys"));
+    }
+    if quota.max_total_size != 0
+        && usage.total_size > quota.max_total_size
+        && usage.total_size > old_usage.total_size
+    {
+        return Err(WasmError::new("quota exceeded"));
+    }
+
+    trinci_sdk::store_account_data_mp!(USAGE_KEY, &usage)
+}
+
+/// Store a value accounting its size in the usage.
+///
+/// Every write of the account data but the contract bookkeeping goes
+/// through here, so that the quota covers it.
+fn store_value(key: &str, data: &[u8]) -> WasmResult<()> {
+    update_usage(key, data.len() as u64)?;
+    trinci_sdk::store_data(key, data);
+    Ok(())
+}
+
+/// Serialize and store a value accounting its size in the usage.
+fn store_record<T: Serialize>(key: &str, value: &T) -> WasmResult<()> {
+    let buf = rmp_serialize(value)?;
+    store_value(key, &buf)
+}
+
+/// Remove a value releasing its size from the usage.
+fn remove_value(key: &str) -> WasmResult<()> {
+    update_usage(key, 0)?;
+    trinci_sdk::remove_data(key);
+    Ok(())
+}
+
+/// Protected keys patterns stored in `buf` (the default ones if not set).
+fn protected_patterns(buf: &[u8]) -> Vec<&str> {
+    rmp_deserialize(buf).unwrap_or_else(|_| DEFAULT_PROTECTED.to_vec())
+}
+
+/// Check if the key can be modified only with the `force` flag.
+fn is_protected(key: &str) -> bool {
+    let buf = trinci_sdk::load_data(PROTECTED_KEY);
+    protected_patterns(&buf)
+        .iter()
+        .any(|pattern| pattern_matches(pattern, key))
+}
+
+/// Store the data incrementing the value version.
+fn write_data(key: &str, data: &[u8]) -> WasmResult<u64> {
+    discard_wraps(key)?;
+    store_value(key, data)?;
+    let version = load_version(key) + 1;
+    trinci_sdk::store_account_data_mp!(&version_key(key), &version)?;
+    Ok(version)
+}
+
+/// Remove the data incrementing the value version.
+///
+/// The version is kept so that it stays monotonic if the key is stored again.
+/// Removing an empty key leaves the version untouched, the versions are not
+/// accounted in the usage and shall not be created without storing a value.
+fn erase_data(key: &str) -> WasmResult<u64> {
+    if trinci_sdk::load_data(key).is_empty() {
+        return Ok(load_version(key));
+    }
+    discard_wraps(key)?;
+    remove_value(key)?;
+    let version = load_version(key) + 1;
+    trinci_sdk::store_account_data_mp!(&version_key(key), &version)?;
+    Ok(version)
+}
+
+/// Load the document stored in `key` (an empty map if the key is empty).
+fn load_document(key: &str) -> WasmResult<Value> {
+    let buf = trinci_sdk::load_data(key);
+    if buf.is_empty() {
+        return Ok(Value::Map(BTreeMap::new()));
+    }
+    rmp_deserialize(&buf).map_err(|_| WasmError::new("invalid document"))
+}
+
+fn store_document(key: &str, doc: &Value) -> WasmResult<()> {
+    let buf = rmp_serialize(doc)?;
+    write_data(key, &buf)?;
+    Ok(())
+}
+
+/// Position of a path segment within an array.
+fn array_index(segment: &str) -> WasmResult<usize> {
+    segment.parse().map_err(|_| WasmError::new("invalid path"))
+}
+
+/// Follow the path inside the document.
+///
+/// If `create` is set the missing map fields are created as empty maps.
+fn field_mut<'a>(doc: &'a mut Value, path: &[&str], create: bool) -> WasmResult<&'a mut Value> {
+    let mut node = doc;
+    for segment in path {
+        node = match node {
+            Value::Map(map) => {
+                let key = Value::String(segment.to_string());
+                if create {
+                    map.entry(key)
+                        .or_insert_with(|| Value::Map(BTreeMap::new()))
+                } else {
+                    map.get_mut(&key)
+                        .ok_or_else(|| WasmError::new("field not found"))?
+                }
+            }
+            Value::Seq(seq) => seq
+                .get_mut(array_index(segment)?)
+                .ok_or_else(|| WasmError::new("field not found"))?,
+            _ => return Err(WasmError::new("invalid path")),
+        };
+    }
+    Ok(node)
+}
+
+/// Account data key of a record addressed by a sha256 (blob, upload, notarization).
+fn hash_key(prefix: &str, hash: &[u8]) -> WasmResult<String> {
+    if hash.len() != 32 {
+        return Err(WasmError::new("invalid hash"));
+    }
+    let hex: String = hash.iter().map(|byte| format!("{:02x}", byte)).collect();
+    Ok(format!("{}{}", prefix, hex))
+}
+
+#[inline]
+fn chunk_key(key: &str, index: u32) -> String {
+    format!("{}/{}", key, index)
+}
+
+fn load_upload(key: &str) -> WasmResult<Upload> {
+    let buf = trinci_sdk::load_data(key);
+    rmp_deserialize(&buf).map_err(|_| WasmError::new("upload not found"))
+}
+
+fn load_blob_info(key: &str) -> WasmResult<BlobInfo> {
+    let buf = trinci_sdk::load_data(key);
+    rmp_deserialize(&buf).map_err(|_| WasmError::new("blob not found"))
+}
+
+/// Remove a pending upload together with its chunks.
+fn discard_upload(key: &str, upload: &Upload) -> WasmResult<()> {
+    for index in 0..upload.chunks {
+        remove_value(&chunk_key(key, index))?;
+    }
+    remove_value(key)
+}
+
+#[inline]
+fn acl_key(pattern: &str) -> String {
+    format!("{}{}", ACL_PREFIX, pattern)
+}
+
+/// Check that the pattern is a key or a key prefix terminated by `*`.
+///
+/// The notarization records are the only reserved keys that can be
+/// granted, the write permission allows to `notarize` them.
+fn check_pattern(pattern: &str) -> WasmResult<()> {
+    let key = pattern.strip_suffix('*').unwrap_or(pattern);
+    if pattern.is_empty() || key.contains('*') {
+        return Err(WasmError::new("invalid pattern"));
+    }
+    if is_reserved(key) && !key.starts_with(NOTARY_PREFIX) {
+        return Err(WasmError::new("reserved key"));
+    }
+    Ok(())
+}
+
+/// Check if the ACL stored for `pattern` grants `access` to `account`.
+fn pattern_allows(pattern: &str, account: &str, access: Access) -> bool {
+    let buf = trinci_sdk::load_data(&acl_key(pattern));
+    let entry: AclEntry = rmp_deserialize(&buf).unwrap_or_default();
+    match entry.get(account) {
+        Some(perms) => match access {
+            Access::Read => perms.read,
+            Access::Write => perms.write,
+            Access::Delete => perms.delete,
+        },
+        None => false,
+    }
+}
+
+/// Patterns having an access control list, stored in `buf`.
+fn acl_patterns(buf: &[u8]) -> BTreeSet<&str> {
+    rmp_deserialize(buf).unwrap_or_default()
+}
+
+/// Check if `account` has been granted `access` to `key`, either directly
+/// or through one of the key prefixes.
+///
+/// Only the ACLs of the registered patterns matching the key are loaded.
+fn is_granted(account: &str, key: &str, access: Access) -> bool {
+    let buf = trinci_sdk::load_data(ACL_INDEX_KEY);
+    acl_patterns(&buf)
+        .iter()
+        .filter(|pattern| pattern_matches(pattern, key))
+        .any(|pattern| pattern_allows(pattern, account, access))
+}
+
+/// Check if the caller is allowed to access the key.
+///
+/// The owner can access every key, the other accounts only the keys
+/// explicitly granted and never the reserved ones.
+fn check_access(ctx: &AppContext, key: &str, access: Access) -> WasmResult<()> {
+    if ctx.caller == ctx.owner || (!is_reserved(key) && is_granted(ctx.caller, key, access)) {
+        Ok(())
+    } else {
+        Err(WasmError::new("not authorized"))
+    }
+}
 
 /// Init the contract
 pub fn init(_ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
     Ok(())
 }
 
+/// Check if the caller is allowed to modify the key.
+///
+/// The reserved keys cannot be modified directly, not even by the owner.
+/// The protected keys can be modified only by the owner with `force` set.
+fn check_modify(ctx: &AppContext, key: &str, access: Access, force: bool) -> WasmResult<()> {
+    check_access(ctx, key, access)?;
+    if is_reserved(key) {
+        return Err(WasmError::new("reserved key"));
+    }
+    if is_protected(key) && !(force && ctx.caller == ctx.owner) {
+        return Err(WasmError::new("protected key"));
+    }
+    Ok(())
+}
+
 /// Remove data into the account data `key` field
 pub fn remove_data(ctx: AppContext, args: RemoveDataArgs) -> WasmResult<()> {
-    if ctx.caller != ctx.owner {
-        return Err(WasmError::new("not authorized"));
-    }
-    trinci_sdk::remove_data(args.key);
+    check_modify(&ctx, args.key, Access::Delete, args.force)?;
+    erase_data(args.key)?;
     Ok(())
 }
 
 /// Store arbitrary data into the account data `key` field
 pub fn store_data(ctx: AppContext, args: StoreDataArgs) -> WasmResult<()> {
-    if ctx.caller != ctx.owner {
-        return Err(WasmError::new("not authorized"));
+    check_modify(&ctx, args.key, Access::Write, args.force)?;
+    write_data(args.key, args.data)?;
+    Ok(())
+}
+
+/// Store arbitrary data into the account data `key` field if the current
+/// version of the value is `expected_version`
+///
+/// Returns the new version of the value.
+pub fn store_data_if_version(ctx: AppContext, args: StoreDataIfVersionArgs) -> WasmResult<u64> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
+    if load_version(args.key) != args.expected_version {
+        return Err(WasmError::new("version mismatch"));
+    }
+    write_data(args.key, args.data)
+}
+
+/// Apply a list of put and remove operations, all or nothing
+///
+/// Every precondition and every operation is checked before modifying
+/// the account data, so a failure leaves the account untouched.
+pub fn batch(ctx: AppContext, args: BatchArgs) -> WasmResult<()> {
+    for cond in args.preconditions.iter() {
+        check_access(&ctx, cond.key, Access::Read)?;
+        let buf = trinci_sdk::load_data(cond.key);
+        // An empty hash requires the key to not exist
+        let matches = if cond.hash.is_empty() {
+            buf.is_empty()
+        } else {
+            trinci_sdk::sha256(&buf) == cond.hash
+        };
+        if !matches {
+            return Err(WasmError::new("precondition failed"));
+        }
+    }
+
+    for op in args.ops.iter() {
+        let access = match op.op {
+            BATCH_PUT => Access::Write,
+            BATCH_REMOVE => Access::Delete,
+            _ => return Err(WasmError::new("invalid operation")),
+        };
+        check_modify(&ctx, op.key, access, false)?;
+    }
+
+    for op in args.ops {
+        if op.op == BATCH_PUT {
+            write_data(op.key, op.data)?;
+        } else {
+            erase_data(op.key)?;
+        }
     }
-    trinci_sdk::store_data(args.key, args.data);
     Ok(())
 }
 
 /// Load arbitrary data from the account data `key` field
 pub fn load_data(ctx: AppContext, args: LoadDataArgs) -> WasmResult<PackedValue> {
+    check_access(&ctx, args.key, Access::Read)?;
+
+    Ok(PackedValue(trinci_sdk::load_data(args.key)))
+}
+
+/// Load arbitrary data from the account data `key` field with its version
+pub fn load_data_versioned(ctx: AppContext, args: LoadDataArgs) -> WasmResult<PackedValue> {
+    check_access(&ctx, args.key, Access::Read)?;
+
+    let data = trinci_sdk::load_data(args.key);
+    let value = VersionedData {
+        version: load_version(args.key),
+        data: &data,
+    };
+
+    let buf = rmp_serialize_named(&value)?;
+    Ok(PackedValue(buf))
+}
+
+/// List the account data keys starting with `prefix`, paginated
+pub fn list_keys(ctx: AppContext, args: ListKeysArgs) -> WasmResult<PackedValue> {
+    let limit = match args.limit {
+        0 => MAX_LIST_LIMIT,
+        limit => limit.min(MAX_LIST_LIMIT),
+    } as usize;
+
+    let mut keys = trinci_sdk::get_data_keys(&format!("{}*", args.prefix))?;
+    keys.sort();
+    let start = match args.cursor {
+        Some(cursor) => keys.partition_point(|key| key.as_str() <= cursor),
+        None => 0,
+    };
+
+    // The access is checked only until the page is full
+    let mut page = Vec::with_capacity(limit);
+    let mut cursor = None;
+    for key in keys.into_iter().skip(start) {
+        if is_reserved(&key) || check_access(&ctx, &key, Access::Read).is_err() {
+            continue;
+        }
+        if page.len() == limit {
+            cursor = page.last().cloned();
+            break;
+        }
+        page.push(key);
+    }
+
+    let buf = rmp_serialize_named(&ListKeysResult { keys: page, cursor })?;
+    Ok(PackedValue(buf))
+}
+
+/// Load arbitrary data from many account data keys
+///
+/// Returns the list of the data, in the same order of the keys.
+pub fn load_many(ctx: AppContext, args: LoadManyArgs) -> WasmResult<PackedValue> {
+    let mut values = Vec::with_capacity(args.keys.len());
+    for key in args.keys {
+        check_access(&ctx, key, Access::Read)?;
+        values.push(ByteBuf::from(trinci_sdk::load_data(key)));
+    }
+
+    let buf = rmp_serialize(&values)?;
+    Ok(PackedValue(buf))
+}
+
+/// Get a field of the document stored in the account data `key` field
+pub fn get_field(ctx: AppContext, args: FieldArgs) -> WasmResult<PackedValue> {
+    check_access(&ctx, args.key, Access::Read)?;
+
+    let mut doc = load_document(args.key)?;
+    let field = field_mut(&mut doc, &args.path, false)?;
+
+    let buf = rmp_serialize(field)?;
+    Ok(PackedValue(buf))
+}
+
+/// Set a field of the document stored in the account data `key` field
+///
+/// The missing map fields along the path are created, an array element
+/// must already exist. An empty path replaces the whole document.
+pub fn set_field(ctx: AppContext, args: SetFieldArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
+
+    let mut doc = load_document(args.key)?;
+    match args.path.split_last() {
+        None => doc = args.value,
+        Some((last, path)) => match field_mut(&mut doc, path, true)? {
+            Value::Map(map) => {
+                map.insert(Value::String(last.to_string()), args.value);
+            }
+            Value::Seq(seq) => match seq.get_mut(array_index(last)?) {
+                Some(item) => *item = args.value,
+                None => return Err(WasmError::new("field not found")),
+            },
+            _ => return Err(WasmError::new("invalid path")),
+        },
+    }
+
+    store_document(args.key, &doc)
+}
+
+/// Remove a field of the document stored in the account data `key` field
+///
+/// The following elements of an array are shifted back.
+pub fn delete_field(ctx: AppContext, args: FieldArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
+
+    let mut doc = load_document(args.key)?;
+    let (last, path) = match args.path.split_last() {
+        Some(split) => split,
+        None => return Err(WasmError::new("invalid path")),
+    };
+    let removed = match field_mut(&mut doc, path, false)? {
+        Value::Map(map) => map.remove(&Value::String(last.to_string())).is_some(),
+        Value::Seq(seq) => {
+            let index = array_index(last)?;
+            if index < seq.len() {
+                seq.remove(index);
+                true
+            } else {
+                false
+            }
+        }
+        _ => return Err(WasmError::new("invalid path")),
+    };
+    if !removed {
+        return Err(WasmError::new("field not found"));
+    }
+
+    store_document(args.key, &doc)
+}
+
+/// Append a value to an array of the document stored in the account data
+/// `key` field
+///
+/// A missing map field is created as an empty array.
+pub fn append(ctx: AppContext, args: SetFieldArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
+
+    let mut doc = load_document(args.key)?;
+    let target = match args.path.split_last() {
+        None => &mut doc,
+        Some((last, path)) => match field_mut(&mut doc, path, true)? {
+            Value::Map(map) => map
+                .entry(Value::String(last.to_string()))
+                .or_insert_with(|| Value::Seq(vec![])),
+            node => field_mut(node, &[last], false)?,
+        },
+    };
+    match target {
+        Value::Seq(seq) => seq.push(args.value),
+        _ => return Err(WasmError::new("not an array")),
+    }
+
+    store_document(args.key, &doc)
+}
+
+/// Check the structure of an envelope.
+fn check_envelope(envelope: &Envelope) -> WasmResult<()> {
+    if envelope.algorithm.is_empty()
+        || envelope.ciphertext.is_empty()
+        || envelope.keys.is_empty()
+        || envelope.keys.len() > MAX_RECIPIENTS
+        || envelope
+            .keys
+            .iter()
+            .any(|(recipient, key)| recipient.is_empty() || key.is_empty())
+    {
+        return Err(WasmError::new("invalid envelope"));
+    }
+    Ok(())
+}
+
+#[inline]
+fn wrap_key(key: &str) -> String {
+    format!("{}{}", WRAP_PREFIX, key)
+}
+
+/// Remove the wrapped keys of the envelope stored in `key`, if any.
+fn discard_wraps(key: &str) -> WasmResult<()> {
+    let wrap_key = wrap_key(key);
+    if !trinci_sdk::load_data(&wrap_key).is_empty() {
+        remove_value(&wrap_key)?;
+    }
+    Ok(())
+}
+
+/// Decode the envelope stored in a value (`buf`) and its wrapped keys
+/// (`wraps`).
+fn parse_envelope<'a>(buf: &'a [u8], wraps: &'a [u8]) -> WasmResult<Envelope<'a>> {
+    if buf.is_empty() {
+        return Err(WasmError::new("envelope not found"));
+    }
+    let data: SealedData = rmp_deserialize(buf).map_err(|_| WasmError::new("invalid envelope"))?;
+    let keys = rmp_deserialize(wraps).map_err(|_| WasmError::new("invalid envelope"))?;
+    let envelope = Envelope {
+        algorithm: data.algorithm,
+        ciphertext: data.ciphertext,
+        keys,
+    };
+    check_envelope(&envelope)?;
+    Ok(envelope)
+}
+
+/// Store an encrypted envelope into the account data `key` field
+pub fn store_envelope(ctx: AppContext, args: StoreEnvelopeArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, args.force)?;
+    check_envelope(&args.envelope)?;
+
+    let data = SealedData {
+        algorithm: args.envelope.algorithm,
+        ciphertext: args.envelope.ciphertext,
+    };
+    let buf = rmp_serialize(&data)?;
+    write_data(args.key, &buf)?;
+    store_record(&wrap_key(args.key), &args.envelope.keys)
+}
+
+/// Load the encrypted envelope stored in the account data `key` field
+pub fn load_envelope(ctx: AppContext, args: LoadDataArgs) -> WasmResult<PackedValue> {
+    check_access(&ctx, args.key, Access::Read)?;
+
+    let buf = trinci_sdk::load_data(args.key);
+    let wraps = trinci_sdk::load_data(&wrap_key(args.key));
+    let envelope = parse_envelope(&buf, &wraps)?;
+
+    let buf = rmp_serialize_named(&envelope)?;
+    Ok(PackedValue(buf))
+}
+
+/// Add a recipient to an envelope, or replace its wrapped key
+///
+/// Only the wrapped keys are stored again, the value and its version are
+/// left untouched.
+pub fn add_recipient(ctx: AppContext, args: AddRecipientArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
     if ctx.caller != ctx.owner {
         return Err(WasmError::new("not authorized"));
     }
 
-    Ok(PackedValue(trinci_sdk::load_data(args.key)))
+    let buf = trinci_sdk::load_data(args.key);
+    let wraps = trinci_sdk::load_data(&wrap_key(args.key));
+    let mut envelope = parse_envelope(&buf, &wraps)?;
+    envelope
+        .keys
+        .insert(args.recipient, ByteBuf::from(args.wrapped_key));
+    check_envelope(&envelope)?;
+
+    store_record(&wrap_key(args.key), &envelope.keys)
 }
 
-/// Call the host function hf_transfer to transfer an *amount* of *asset* from the *caller account* to the *dest *account*
-pub fn transfer(ctx: AppContext, args: TransferArgs) -> WasmResult<()> {
+/// Revoke a recipient of an envelope
+///
+/// The last recipient cannot be revoked, remove the data instead. Only the
+/// wrapped keys are stored again, the value and its version are left
+/// untouched.
+pub fn revoke_recipient(ctx: AppContext, args: RevokeRecipientArgs) -> WasmResult<()> {
+    check_modify(&ctx, args.key, Access::Write, false)?;
     if ctx.caller != ctx.owner {
         return Err(WasmError::new("not authorized"));
     }
 
-    trinci_sdk::log!(
-        "Transfering {} {}\nfrom: {}\nto: {}",
-        args.units,
-        args.asset,
-        ctx.owner,
-        args.to
-    );
+    let buf = trinci_sdk::load_data(args.key);
+    let wraps = trinci_sdk::load_data(&wrap_key(args.key));
+    let mut envelope = parse_envelope(&buf, &wraps)?;
+    if envelope.keys.remove(args.recipient).is_none() {
+        return Err(WasmError::new("recipient not found"));
+    }
+    if envelope.keys.is_empty() {
+        return Err(WasmError::new("last recipient"));
+    }
 
-    trinci_sdk::asset_transfer(ctx.caller, args.to, args.asset, args.units)
+    store_record(&wrap_key(args.key), &envelope.keys)
 }
 
-/// Call the host function hf_balance to get the caller account balance
-fn balance(ctx: AppContext, args: BalanceArgs) -> WasmResult<u64> {
+/// Begin the chunked upload of a blob
+///
+/// Returns `false` if a blob with the same hash is already stored, in that
+/// case there is nothing to upload. Beginning again a pending upload
+/// discards the chunks already sent. The blob size is limited to
+/// `MAX_BLOB_SIZE`, as the blob is verified as a whole.
+pub fn begin_upload(ctx: AppContext, args: BeginUploadArgs) -> WasmResult<bool> {
     if ctx.caller != ctx.owner {
         return Err(WasmError::new("not authorized"));
     }
+    if !trinci_sdk::load_data(&hash_key(BLOB_PREFIX, args.hash)?).is_empty() {
+        return Ok(false);
+    }
+    if args.chunks == 0 || args.chunks as u64 > args.size {
+        return Err(WasmError::new("invalid arguments"));
+    }
+    if args.size > MAX_BLOB_SIZE {
+        return Err(WasmError::new("blob too large"));
+    }
 
-    trinci_sdk::asset_balance(args.asset)
+    let key = hash_key(UPLOAD_PREFIX, args.hash)?;
+    if let Ok(upload) = load_upload(&key) {
+        discard_upload(&key, &upload)?;
+    }
+    let upload = Upload {
+        size: args.size,
+        chunks: args.chunks,
+    };
+    store_record(&key, &upload)?;
+    Ok(true)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use trinci_sdk::{not_wasm, tai::Asset};
+/// Send a chunk of a pending upload
+pub fn put_chunk(ctx: AppContext, args: PutChunkArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
 
-    const CALLER_ID: &str = "QmYHnEQLdf5h7KYbjFPuHSRk2SPgdXrJWFh5W696HPfq7i";
-    const ASSET_ID: &str = "QmSCRCPFznxEX6S316M4yVmxdxPB6XN63ob2LjFYkP6MLq";
-    const DATA_KEY: &str = "data";
+    let key = hash_key(UPLOAD_PREFIX, args.hash)?;
+    let upload = load_upload(&key)?;
+    if args.index >= upload.chunks {
+        return Err(WasmError::new("invalid chunk index"));
+    }
+    if args.data.is_empty() {
+        return Err(WasmError::new("invalid arguments"));
+    }
 
-    #[test]
-    fn store_data_test() {
-        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
-        let args = StoreDataArgs {
-            key: DATA_KEY,
-            data: &[1, 2, 3],
-        };
+    store_value(&chunk_key(&key, args.index), args.data)
+}
 
-        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+/// Commit a pending upload once all its chunks have been sent
+///
+/// The blob, at most `MAX_BLOB_SIZE` bytes, is verified against its sha256
+/// and moved into chunks of `BLOB_CHUNK_SIZE` bytes. A size or hash
+/// mismatch fails the call, leaving the pending upload untouched.
+pub fn commit_upload(ctx: AppContext, args: BlobArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
 
-        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
-        assert_eq!(data, &[1, 2, 3]);
+    let key = hash_key(UPLOAD_PREFIX, args.hash)?;
+    let upload = load_upload(&key)?;
+    let mut blob = Vec::with_capacity(upload.size as usize);
+    for index in 0..upload.chunks {
+        let chunk = trinci_sdk::load_data(&chunk_key(&key, index));
+        if chunk.is_empty() {
+            return Err(WasmError::new("missing chunk"));
+        }
+        if (blob.len() + chunk.len()) as u64 > upload.size {
+            return Err(WasmError::new("size mismatch"));
+        }
+        blob.extend_from_slice(&chunk);
+    }
+    if blob.len() as u64 != upload.size {
+        return Err(WasmError::new("size mismatch"));
+    }
+    if trinci_sdk::sha256(&blob) != args.hash {
+        return Err(WasmError::new("hash mismatch"));
+    }
+    discard_upload(&key, &upload)?;
+
+    // The blob may have been committed meanwhile by another upload
+    let key = hash_key(BLOB_PREFIX, args.hash)?;
+    if load_blob_info(&key).is_ok() {
+        return Ok(());
+    }
+    let mut chunks = 0;
+    for chunk in blob.chunks(BLOB_CHUNK_SIZE as usize) {
+        store_value(&chunk_key(&key, chunks), chunk)?;
+        chunks += 1;
+    }
+    let info = BlobInfo {
+        size: upload.size,
+        chunk_size: BLOB_CHUNK_SIZE,
+        chunks,
+    };
+    store_record(&key, &info)
+}
+
+/// Get the size and the layout of a committed blob
+pub fn blob_info(ctx: AppContext, args: BlobArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let info = load_blob_info(&hash_key(BLOB_PREFIX, args.hash)?)?;
+
+    let buf = rmp_serialize_named(&info)?;
+    Ok(PackedValue(buf))
+}
+
+/// Load a range of bytes of a committed blob
+///
+/// The range is truncated at the end of the blob.
+pub fn load_chunk(ctx: AppContext, args: LoadChunkArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let key = hash_key(BLOB_PREFIX, args.hash)?;
+    let info = load_blob_info(&key)?;
+    if args.offset > info.size || args.length > MAX_RANGE_LENGTH {
+        return Err(WasmError::new("invalid range"));
+    }
+
+    let chunk_size = info.chunk_size as u64;
+    let end = info.size.min(args.offset + args.length as u64);
+    let mut data = Vec::with_capacity((end - args.offset) as usize);
+    let mut pos = args.offset;
+    while pos < end {
+        let index = pos / chunk_size;
+        let base = index * chunk_size;
+        let chunk = trinci_sdk::load_data(&chunk_key(&key, index as u32));
+        let range = (pos - base) as usize..(end - base).min(chunk_size) as usize;
+        match chunk.get(range) {
+            Some(bytes) => data.extend_from_slice(bytes),
+            None => return Err(WasmError::new("blob corrupted")),
+        }
+        pos = base + chunk_size;
+    }
+
+    Ok(PackedValue(data))
+}
+
+/// Notarize the sha256 digest of a document
+///
+/// The record holds the caller account and the block time. Besides the
+/// owner, only the accounts granted to write the record can notarize.
+pub fn notarize(ctx: AppContext, args: NotarizeArgs) -> WasmResult<()> {
+    let key = hash_key(NOTARY_PREFIX, args.hash)?;
+    if ctx.caller != ctx.owner && !is_granted(ctx.caller, &key, Access::Write) {
+        return Err(WasmError::new("not authorized"));
+    }
+    if args.metadata.map_or(0, str::len) > MAX_NOTARY_METADATA {
+        return Err(WasmError::new("metadata too large"));
+    }
+    if !trinci_sdk::load_data(&key).is_empty() {
+        return Err(WasmError::new("already notarized"));
+    }
+
+    let record = NotaryRecord {
+        submitter: ctx.caller,
+        timestamp: trinci_sdk::get_block_time(),
+        metadata: args.metadata,
+    };
+    store_record(&key, &record)
+}
+
+/// Get the notarization record of a sha256 digest
+pub fn verify(_ctx: AppContext, args: VerifyArgs) -> WasmResult<PackedValue> {
+    let buf = trinci_sdk::load_data(&hash_key(NOTARY_PREFIX, args.hash)?);
+    let record: NotaryRecord =
+        rmp_deserialize(&buf).map_err(|_| WasmError::new("not notarized"))?;
+
+    let buf = rmp_serialize_named(&record)?;
+    Ok(PackedValue(buf))
+}
+
+/// Set the account data quota
+///
+/// A zero limit is not enforced, an all zeros quota removes it. The usage
+/// is computed again from the account data.
+pub fn set_quota(ctx: AppContext, args: Quota) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    if args.max_keys == 0 && args.max_value_size == 0 && args.max_total_size == 0 {
+        trinci_sdk::remove_data(QUOTA_KEY);
+        trinci_sdk::remove_data(USAGE_KEY);
+        return Ok(());
+    }
+    trinci_sdk::store_account_data_mp!(QUOTA_KEY, &args)?;
+    trinci_sdk::store_account_data_mp!(USAGE_KEY, &compute_usage()?)
+}
+
+/// Get the account data quota and the current usage
+pub fn get_quota(ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let status = match load_quota() {
+        Some(quota) => QuotaStatus {
+            quota,
+            usage: load_usage(),
+        },
+        None => QuotaStatus {
+            quota: Quota::default(),
+            usage: compute_usage()?,
+        },
+    };
+
+    let buf = rmp_serialize_named(&status)?;
+    Ok(PackedValue(buf))
+}
+
+/// Set the list of the protected keys, replacing the default one
+pub fn set_protected(ctx: AppContext, args: SetProtectedArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    for pattern in args.patterns.iter() {
+        check_pattern(pattern)?;
+    }
+
+    trinci_sdk::store_account_data_mp!(PROTECTED_KEY, &args.patterns)
+}
+
+/// Get the list of the protected keys
+pub fn get_protected(ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let buf = trinci_sdk::load_data(PROTECTED_KEY);
+    let patterns = protected_patterns(&buf);
+
+    let buf = rmp_serialize(&patterns)?;
+    Ok(PackedValue(buf))
+}
+
+/// Grant to an account the permissions on a key or on a key prefix
+pub fn grant(ctx: AppContext, args: GrantArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    check_pattern(args.pattern)?;
+
+    let key = acl_key(args.pattern);
+    let buf = trinci_sdk::load_data(&key);
+    let mut entry: AclEntry = rmp_deserialize(&buf).unwrap_or_default();
+    entry.insert(args.account, args.permissions);
+    trinci_sdk::store_account_data_mp!(&key, &entry)?;
+
+    let buf = trinci_sdk::load_data(ACL_INDEX_KEY);
+    let mut patterns = acl_patterns(&buf);
+    if patterns.insert(args.pattern) {
+        trinci_sdk::store_account_data_mp!(ACL_INDEX_KEY, &patterns)?;
+    }
+    Ok(())
+}
+
+/// Revoke all the permissions of an account on a key or on a key prefix
+pub fn revoke(ctx: AppContext, args: RevokeArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    check_pattern(args.pattern)?;
+
+    let key = acl_key(args.pattern);
+    let buf = trinci_sdk::load_data(&key);
+    let mut entry: AclEntry = rmp_deserialize(&buf).unwrap_or_default();
+    if entry.remove(args.account).is_none() {
+        return Err(WasmError::new("grant not found"));
+    }
+
+    if !entry.is_empty() {
+        return trinci_sdk::store_account_data_mp!(&key, &entry);
+    }
+    trinci_sdk::remove_data(&key);
+
+    let buf = trinci_sdk::load_data(ACL_INDEX_KEY);
+    let mut patterns = acl_patterns(&buf);
+    patterns.remove(args.pattern);
+    trinci_sdk::store_account_data_mp!(ACL_INDEX_KEY, &patterns)
+}
+
+/// Get the access control list of a key or of a key prefix
+pub fn get_acl(ctx: AppContext, args: GetAclArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    check_pattern(args.pattern)?;
+
+    let buf = trinci_sdk::load_data(&acl_key(args.pattern));
+    let entry: AclEntry = rmp_deserialize(&buf).unwrap_or_default();
+
+    let buf = rmp_serialize_named(&entry)?;
+    Ok(PackedValue(buf))
+}
+
+#[inline]
+fn operator_key(account: &str) -> String {
+    format!("{}{}", OPERATOR_PREFIX, account)
+}
+
+#[inline]
+fn spend_key(index: u64) -> String {
+    format!("{}{:020}", SPEND_PREFIX, index)
+}
+
+fn load_spend_count() -> u64 {
+    let buf = trinci_sdk::load_data(SPEND_COUNT_KEY);
+    rmp_deserialize(&buf).unwrap_or_default()
+}
+
+/// Check that the caller is an operator allowed to transfer, account the
+/// spend in the current period and log it.
+///
+/// A limit with a zero period never resets: it caps the operator spends
+/// over the whole operator lifetime.
+fn operator_spend(ctx: &AppContext, args: &TransferArgs) -> WasmResult<()> {
+    let key = operator_key(ctx.caller);
+    let buf = trinci_sdk::load_data(&key);
+    let mut operator: Operator =
+        rmp_deserialize(&buf).map_err(|_| WasmError::new("not authorized"))?;
+
+    if !operator.destinations.is_empty() && !operator.destinations.contains(&args.to) {
+        return Err(WasmError::new("destination not allowed"));
+    }
+    let limit = match operator.limits.get(args.asset) {
+        Some(limit) => *limit,
+        None => return Err(WasmError::new("asset not allowed")),
+    };
+
+    let now = trinci_sdk::get_block_time();
+    let spent = operator.spent.entry(args.asset).or_insert(Spent {
+        since: now,
+        units: 0,
+    });
+    if limit.period != 0 && now >= spent.since.saturating_add(limit.period) {
+        spent.since = now;
+        spent.units = 0;
+    }
+    let units = spent
+        .units
+        .checked_add(args.units)
+        .ok_or_else(|| WasmError::new("spent units overflow"))?;
+    if units > limit.units {
+        return Err(WasmError::new("spending limit exceeded"));
+    }
+    spent.units = units;
+    store_record(&key, &operator)?;
+
+    let index = load_spend_count();
+    let record = SpendRecord {
+        operator: ctx.caller,
+        to: args.to,
+        asset: args.asset,
+        units: args.units,
+        timestamp: now,
+    };
+    store_record(&spend_key(index), &record)?;
+    trinci_sdk::store_account_data_mp!(SPEND_COUNT_KEY, &(index + 1))
+}
+
+/// Authorize an operator to transfer from the account, replacing its
+/// previous limits
+pub fn set_operator(ctx: AppContext, args: SetOperatorArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    if args.account == ctx.owner || args.limits.is_empty() {
+        return Err(WasmError::new("invalid arguments"));
+    }
+
+    let operator = Operator {
+        limits: args.limits,
+        destinations: args.destinations,
+        spent: BTreeMap::new(),
+    };
+    store_record(&operator_key(args.account), &operator)
+}
+
+/// Revoke an operator
+pub fn revoke_operator(ctx: AppContext, args: OperatorArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let key = operator_key(args.account);
+    if trinci_sdk::load_data(&key).is_empty() {
+        return Err(WasmError::new("operator not found"));
+    }
+    remove_value(&key)
+}
+
+/// Get an operator limits and current spends (only the owner or the operator)
+pub fn get_operator(ctx: AppContext, args: OperatorArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner && ctx.caller != args.account {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let buf = trinci_sdk::load_data(&operator_key(args.account));
+    let operator: Operator =
+        rmp_deserialize(&buf).map_err(|_| WasmError::new("operator not found"))?;
+
+    let buf = rmp_serialize_named(&operator)?;
+    Ok(PackedValue(buf))
+}
+
+/// Get the operators spend log, paginated
+pub fn get_spends(ctx: AppContext, args: GetSpendsArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    let limit = match args.limit {
+        0 => MAX_LIST_LIMIT,
+        limit => limit.min(MAX_LIST_LIMIT),
+    } as u64;
+
+    let end = load_spend_count().min(args.start.saturating_add(limit));
+    let bufs: Vec<Vec<u8>> = (args.start..end)
+        .map(|index| trinci_sdk::load_data(&spend_key(index)))
+        .collect();
+    let records = bufs
+        .iter()
+        .map(|buf| rmp_deserialize(buf))
+        .collect::<WasmResult<Vec<SpendRecord>>>()?;
+
+    let buf = rmp_serialize_named(&records)?;
+    Ok(PackedValue(buf))
+}
+
+/// Call the host function hf_transfer to transfer an *amount* of *asset* from the *caller account* to the *dest *account*
+///
+/// The operators can transfer within their spending limits.
+pub fn transfer(ctx: AppContext, args: TransferArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        operator_spend(&ctx, &args)?;
+    }
+
+    trinci_sdk::log!(
+        "Transfering {} {}\nfrom: {}\nto: {}",
+        args.units,
+        args.asset,
+        ctx.owner,
+        args.to
+    );
+
+    trinci_sdk::asset_transfer(ctx.owner, args.to, args.asset, args.units)
+}
+
+/// Execute many transfers from the account, all or nothing
+///
+/// Returns the result of each transfer. If a transfer fails the whole
+/// call fails, reporting the position of the failed transfer.
+pub fn multi_transfer(ctx: AppContext, args: MultiTransferArgs) -> WasmResult<PackedValue> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    if args.transfers.is_empty() {
+        return Err(WasmError::new("invalid arguments"));
+    }
+
+    let mut results = Vec::with_capacity(args.transfers.len());
+    for (index, leg) in args.transfers.iter().enumerate() {
+        trinci_sdk::asset_transfer(ctx.caller, leg.to, leg.asset, leg.units)
+            .map_err(|err| WasmError::new(&format!("transfer {} failed: {}", index, err)))?;
+        results.push(TransferResult {
+            to: leg.to,
+            asset: leg.asset,
+            units: leg.units,
+            balance: trinci_sdk::asset_balance(leg.asset)?,
+        });
+    }
+
+    let buf = rmp_serialize_named(&results)?;
+    Ok(PackedValue(buf))
+}
+
+#[inline]
+fn order_key(id: &str) -> String {
+    format!("{}{}", ORDER_PREFIX, id)
+}
+
+/// Register a standing order
+pub fn add_order(ctx: AppContext, args: AddOrderArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+    if args.id.is_empty() || args.units == 0 {
+        return Err(WasmError::new("invalid arguments"));
+    }
+
+    let key = order_key(args.id);
+    if !trinci_sdk::load_data(&key).is_empty() {
+        return Err(WasmError::new("order already exists"));
+    }
+    let order = Order {
+        to: args.to,
+        asset: args.asset,
+        units: args.units,
+        reward: args.reward,
+        interval: args.interval,
+        next: args.start,
+        executions: 0,
+        active: true,
+    };
+    store_record(&key, &order)
+}
+
+/// Remove a standing order
+pub fn remove_order(ctx: AppContext, args: OrderArgs) -> WasmResult<()> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    let key = order_key(args.id);
+    if trinci_sdk::load_data(&key).is_empty() {
+        return Err(WasmError::new("order not found"));
+    }
+    remove_value(&key)
+}
+
+/// Get a standing order
+pub fn get_order(_ctx: AppContext, args: OrderArgs) -> WasmResult<PackedValue> {
+    let buf = trinci_sdk::load_data(&order_key(args.id));
+    let order: Order = rmp_deserialize(&buf).map_err(|_| WasmError::new("order not found"))?;
+
+    let buf = rmp_serialize_named(&order)?;
+    Ok(PackedValue(buf))
+}
+
+/// Execute the due standing orders
+///
+/// Each order executes at most one due period per call. The orders whose
+/// transfer or reward cannot be paid are skipped and left due, the other
+/// orders are executed anyway. The caller receives the reward of every
+/// executed order. Returns the identifiers of the executed orders.
+pub fn execute_due(ctx: AppContext, _args: PackedValue) -> WasmResult<Vec<String>> {
+    let now = trinci_sdk::get_block_time();
+    let mut keys = trinci_sdk::get_data_keys(&format!("{}*", ORDER_PREFIX))?;
+    keys.sort();
+
+    let mut executed = Vec::new();
+    for key in keys {
+        if executed.len() == MAX_DUE_ORDERS {
+            break;
+        }
+        let buf = trinci_sdk::load_data(&key);
+        let mut order: Order = match rmp_deserialize(&buf) {
+            Ok(order) => order,
+            Err(_) => continue,
+        };
+        if !order.active || order.next > now {
+            continue;
+        }
+        // The order is executed only if the balance covers the reward too
+        let reward = if ctx.caller != ctx.owner {
+            order.reward
+        } else {
+            0
+        };
+        let payable = match order.units.checked_add(reward) {
+            Some(due) => trinci_sdk::asset_balance(order.asset).is_ok_and(|units| units >= due),
+            None => false,
+        };
+        if !payable {
+            continue;
+        }
+        if trinci_sdk::asset_transfer(ctx.owner, order.to, order.asset, order.units).is_err() {
+            continue;
+        }
+        // A reward failing anyway must not fail the other orders
+        if reward > 0 {
+            let _ = trinci_sdk::asset_transfer(ctx.owner, ctx.caller, order.asset, reward);
+        }
+
+        order.executions += 1;
+        match order.interval {
+            0 => order.active = false,
+            interval => order.next += interval,
+        }
+        store_record(&key, &order)?;
+        executed.push(key[ORDER_PREFIX.len()..].to_string());
+    }
+
+    Ok(executed)
+}
+
+/// Call the host function hf_balance to get the caller account balance
+fn balance(ctx: AppContext, args: BalanceArgs) -> WasmResult<u64> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    trinci_sdk::asset_balance(args.asset)
+}
+
+/// Get the account balance of many assets
+///
+/// Returns the list of the balances, in the same order of the assets.
+fn balances(ctx: AppContext, args: BalancesArgs) -> WasmResult<Vec<u64>> {
+    if ctx.caller != ctx.owner {
+        return Err(WasmError::new("not authorized"));
+    }
+
+    args.assets
+        .iter()
+        .map(|asset| trinci_sdk::asset_balance(asset))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use trinci_sdk::{not_wasm, rmp_serialize, tai::Asset, value};
+
+    const CALLER_ID: &str = "QmYHnEQLdf5h7KYbjFPuHSRk2SPgdXrJWFh5W696HPfq7i";
+    const ASSET_ID: &str = "QmSCRCPFznxEX6S316M4yVmxdxPB6XN63ob2LjFYkP6MLq";
+    const GRANTEE_ID: &str = "QmGranteeEX6S316M4yVmxdxPB6XN63ob2LjFYkP6MLq";
+    const DATA_KEY: &str = "data";
+
+    fn permissions(read: bool, write: bool, delete: bool) -> Permissions {
+        Permissions {
+            read,
+            write,
+            delete,
+        }
+    }
+
+    fn set_grant(pattern: &str, account: &str, perms: Permissions) {
+        let mut entry = AclEntry::new();
+        entry.insert(account, perms);
+        let buf = rmp_serialize(&entry).unwrap();
+        not_wasm::set_account_data(CALLER_ID, &acl_key(pattern), &buf);
+
+        let buf = not_wasm::get_account_data(CALLER_ID, ACL_INDEX_KEY);
+        let mut patterns = acl_patterns(&buf);
+        patterns.insert(pattern);
+        let buf = rmp_serialize(&patterns).unwrap();
+        not_wasm::set_account_data(CALLER_ID, ACL_INDEX_KEY, &buf);
+    }
+
+    #[test]
+    fn store_data_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = StoreDataArgs {
+            key: DATA_KEY,
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn unauthorized_store_data() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+
+        let args = StoreDataArgs {
+            key: DATA_KEY,
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn load_data_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let data = not_wasm::call_wrap(load_data, ctx, args).unwrap();
+
+        assert_eq!(*data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn load_data_not_existing_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = LoadDataArgs { key: "config" };
+
+        let data = not_wasm::call_wrap(load_data, ctx, args).unwrap();
+
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn unauthorized_load_data() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let err = not_wasm::call_wrap(load_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn remove_data_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn unauthorized_remove_data() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(remove_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn load_data_with_key_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let data = not_wasm::call_wrap(load_data, ctx, args).unwrap();
+
+        assert_eq!(*data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn load_data_granted_to_another_account() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, true, true));
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let err = not_wasm::call_wrap(load_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn store_data_with_prefix_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant("shared:*", GRANTEE_ID, permissions(false, true, false));
+        let args = StoreDataArgs {
+            key: "shared:data",
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, "shared:data");
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn store_data_outside_granted_prefix() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant("shared:*", GRANTEE_ID, permissions(false, true, false));
+        let args = StoreDataArgs {
+            key: "private:data",
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn store_data_with_read_only_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = StoreDataArgs {
+            key: DATA_KEY,
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn store_data_on_reserved_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = StoreDataArgs {
+            key: "__sys/acl:data",
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    #[test]
+    fn store_data_outside_reserved_namespace() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = StoreDataArgs {
+            key: "acl:data",
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, "acl:data");
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn load_data_on_reserved_key_with_global_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant("*", GRANTEE_ID, permissions(true, true, true));
+        let args = LoadDataArgs { key: "__sys/acl:*" };
+
+        let err = not_wasm::call_wrap(load_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn remove_data_with_delete_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_grant("da*", GRANTEE_ID, permissions(false, false, true));
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn remove_data_on_reserved_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = RemoveDataArgs {
+            key: "__sys/acl:data",
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(remove_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    fn set_version(key: &str, version: u64) {
+        let buf = rmp_serialize(&version).unwrap();
+        not_wasm::set_account_data(CALLER_ID, &version_key(key), &buf);
+    }
+
+    fn get_version(key: &str) -> u64 {
+        let buf = not_wasm::get_account_data(CALLER_ID, &version_key(key));
+        rmp_deserialize(&buf).unwrap()
+    }
+
+    #[test]
+    fn store_data_increments_version() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_version(DATA_KEY, 4);
+        let args = StoreDataArgs {
+            key: DATA_KEY,
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        assert_eq!(get_version(DATA_KEY), 5);
+    }
+
+    #[test]
+    fn remove_data_keeps_version() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_version(DATA_KEY, 1);
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        assert_eq!(get_version(DATA_KEY), 2);
+    }
+
+    #[test]
+    fn load_data_versioned_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_version(DATA_KEY, 3);
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let buf = not_wasm::call_wrap(load_data_versioned, ctx, args).unwrap();
+
+        let value: VersionedData = rmp_deserialize(&buf).unwrap();
+        assert_eq!(value.version, 3);
+        assert_eq!(value.data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn load_data_versioned_never_written() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let buf = not_wasm::call_wrap(load_data_versioned, ctx, args).unwrap();
+
+        let value: VersionedData = rmp_deserialize(&buf).unwrap();
+        assert_eq!(value.version, 0);
+        assert!(value.data.is_empty());
+    }
+
+    #[test]
+    fn store_data_if_version_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_version(DATA_KEY, 3);
+        let args = StoreDataIfVersionArgs {
+            key: DATA_KEY,
+            data: &[4, 5, 6],
+            expected_version: 3,
+        };
+
+        let version = not_wasm::call_wrap(store_data_if_version, ctx, args).unwrap();
+
+        assert_eq!(version, 4);
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[4, 5, 6]);
+    }
+
+    #[test]
+    fn store_data_if_version_mismatch() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        set_version(DATA_KEY, 3);
+        let args = StoreDataIfVersionArgs {
+            key: DATA_KEY,
+            data: &[4, 5, 6],
+            expected_version: 2,
+        };
+
+        let err = not_wasm::call_wrap(store_data_if_version, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "version mismatch");
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn store_data_if_version_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        let args = StoreDataIfVersionArgs {
+            key: DATA_KEY,
+            data: &[4, 5, 6],
+            expected_version: 2,
+        };
+
+        let err = not_wasm::call_wrap(store_data_if_version, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn list_keys_hides_reserved_keys() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1]);
+        set_version(DATA_KEY, 1);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = ListKeysArgs {
+            prefix: "",
+            cursor: None,
+            limit: 0,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec![DATA_KEY]);
+    }
+
+    #[test]
+    fn list_keys_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, "shared:c", &[3]);
+        not_wasm::set_account_data(CALLER_ID, "shared:a", &[1]);
+        not_wasm::set_account_data(CALLER_ID, "shared:b", &[2]);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[4]);
+        let args = ListKeysArgs {
+            prefix: "shared:",
+            cursor: None,
+            limit: 2,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec!["shared:a", "shared:b"]);
+        assert_eq!(res.cursor.as_deref(), Some("shared:b"));
+    }
+
+    #[test]
+    fn list_keys_last_page() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, "shared:c", &[3]);
+        not_wasm::set_account_data(CALLER_ID, "shared:a", &[1]);
+        not_wasm::set_account_data(CALLER_ID, "shared:b", &[2]);
+        let args = ListKeysArgs {
+            prefix: "shared:",
+            cursor: Some("shared:b"),
+            limit: 2,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec!["shared:c"]);
+        assert_eq!(res.cursor, None);
+    }
+
+    #[test]
+    fn list_keys_with_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        not_wasm::set_account_data(CALLER_ID, "shared:a", &[1]);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[4]);
+        set_grant("shared:*", GRANTEE_ID, permissions(true, false, false));
+        let args = ListKeysArgs {
+            prefix: "",
+            cursor: None,
+            limit: 0,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec!["shared:a"]);
+        assert_eq!(res.cursor, None);
+    }
+
+    #[test]
+    fn list_keys_with_grant_paginated() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        not_wasm::set_account_data(CALLER_ID, "private:a", &[1]);
+        not_wasm::set_account_data(CALLER_ID, "shared:a", &[2]);
+        not_wasm::set_account_data(CALLER_ID, "shared:b", &[3]);
+        not_wasm::set_account_data(CALLER_ID, "shared:c", &[4]);
+        set_grant("shared:*", GRANTEE_ID, permissions(true, false, false));
+        let args = ListKeysArgs {
+            prefix: "",
+            cursor: None,
+            limit: 2,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec!["shared:a", "shared:b"]);
+        assert_eq!(res.cursor.as_deref(), Some("shared:b"));
+
+        let args = ListKeysArgs {
+            prefix: "",
+            cursor: res.cursor.as_deref(),
+            limit: 2,
+        };
+
+        let buf = not_wasm::call_wrap(list_keys, ctx, args).unwrap();
+
+        let res: ListKeysResult = rmp_deserialize(&buf).unwrap();
+        assert_eq!(res.keys, vec!["shared:c"]);
+        assert_eq!(res.cursor, None);
+    }
+
+    #[test]
+    fn load_many_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::set_account_data(CALLER_ID, "mydata", &[4, 5]);
+        let args = LoadManyArgs {
+            keys: vec!["mydata", "missing", DATA_KEY],
+        };
+
+        let buf = not_wasm::call_wrap(load_many, ctx, args).unwrap();
+
+        let values: Vec<ByteBuf> = rmp_deserialize(&buf).unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].as_slice(), &[4, 5]);
+        assert!(values[1].is_empty());
+        assert_eq!(values[2].as_slice(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn load_many_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::set_account_data(CALLER_ID, "mydata", &[4, 5]);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = LoadManyArgs {
+            keys: vec![DATA_KEY, "mydata"],
+        };
+
+        let err = not_wasm::call_wrap(load_many, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    fn put_op<'a>(key: &'a str, data: &'a [u8]) -> BatchOp<'a> {
+        BatchOp {
+            op: "put",
+            key,
+            data,
+        }
+    }
+
+    fn remove_op(key: &str) -> BatchOp<'_> {
+        BatchOp {
+            op: "remove",
+            key,
+            data: &[],
+        }
+    }
+
+    #[test]
+    fn batch_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, "mydata", &[4, 5]);
+        let args = BatchArgs {
+            ops: vec![put_op(DATA_KEY, &[1, 2, 3]), remove_op("mydata")],
+            preconditions: vec![],
+        };
+
+        not_wasm::call_wrap(batch, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[1, 2, 3]);
+        let data = not_wasm::get_account_data(CALLER_ID, "mydata");
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn batch_with_preconditions() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        let args = BatchArgs {
+            ops: vec![put_op(DATA_KEY, &[4, 5, 6]), put_op("mydata", &[7])],
+            preconditions: vec![
+                Precondition {
+                    key: DATA_KEY,
+                    hash: &hash,
+                },
+                Precondition {
+                    key: "mydata",
+                    hash: &[],
+                },
+            ],
+        };
+
+        not_wasm::call_wrap(batch, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[4, 5, 6]);
+        let data = not_wasm::get_account_data(CALLER_ID, "mydata");
+        assert_eq!(data, &[7]);
+    }
+
+    #[test]
+    fn batch_failed_precondition() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let hash = trinci_sdk::sha256(&[9]);
+        let args = BatchArgs {
+            ops: vec![put_op(DATA_KEY, &[4, 5, 6])],
+            preconditions: vec![Precondition {
+                key: DATA_KEY,
+                hash: &hash,
+            }],
+        };
+
+        let err = not_wasm::call_wrap(batch, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "precondition failed");
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn batch_existing_key_precondition() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = BatchArgs {
+            ops: vec![put_op(DATA_KEY, &[4, 5, 6])],
+            preconditions: vec![Precondition {
+                key: DATA_KEY,
+                hash: &[],
+            }],
+        };
+
+        let err = not_wasm::call_wrap(batch, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "precondition failed");
+    }
+
+    #[test]
+    fn batch_invalid_operation() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let mut op = put_op("mydata", &[7]);
+        op.op = "append";
+        let args = BatchArgs {
+            ops: vec![put_op(DATA_KEY, &[1, 2, 3]), op],
+            preconditions: vec![],
+        };
+
+        let err = not_wasm::call_wrap(batch, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid operation");
+        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn batch_partially_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant("shared:*", GRANTEE_ID, permissions(false, true, false));
+        let args = BatchArgs {
+            ops: vec![put_op("shared:data", &[1, 2, 3]), put_op(DATA_KEY, &[4])],
+            preconditions: vec![],
+        };
+
+        let err = not_wasm::call_wrap(batch, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+        let data = not_wasm::get_account_data(CALLER_ID, "shared:data");
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn batch_on_reserved_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = BatchArgs {
+            ops: vec![remove_op("__sys/acl:data")],
+            preconditions: vec![],
+        };
+
+        let err = not_wasm::call_wrap(batch, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    fn set_document(doc: &Value) {
+        let buf = rmp_serialize(doc).unwrap();
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &buf);
+    }
+
+    fn get_document() -> Value {
+        let buf = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        rmp_deserialize(&buf).unwrap()
+    }
+
+    fn create_document() -> Value {
+        value!({
+            "name": "Alice",
+            "tags": ["a", "b"],
+        })
+    }
+
+    fn field_args<'a>(path: &[&'a str]) -> FieldArgs<'a> {
+        FieldArgs {
+            key: DATA_KEY,
+            path: path.to_vec(),
+        }
+    }
+
+    fn set_field_args<'a>(path: &[&'a str], value: Value) -> SetFieldArgs<'a> {
+        SetFieldArgs {
+            key: DATA_KEY,
+            path: path.to_vec(),
+            value,
+        }
+    }
+
+    #[test]
+    fn get_field_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+
+        let buf = not_wasm::call_wrap(get_field, ctx, field_args(&["tags", "1"])).unwrap();
+
+        let field: Value = rmp_deserialize(&buf).unwrap();
+        assert_eq!(field, value!("b"));
+    }
+
+    #[test]
+    fn get_field_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+
+        let err = not_wasm::call_wrap(get_field, ctx, field_args(&["tags", "2"])).unwrap_err();
+
+        assert_eq!(err.to_string(), "field not found");
+    }
+
+    #[test]
+    fn get_field_with_grant() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_document(&create_document());
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+
+        let buf = not_wasm::call_wrap(get_field, ctx, field_args(&["name"])).unwrap();
+
+        let field: Value = rmp_deserialize(&buf).unwrap();
+        assert_eq!(field, value!("Alice"));
+    }
+
+    #[test]
+    fn get_field_invalid_document() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[0xc1]);
+
+        let err = not_wasm::call_wrap(get_field, ctx, field_args(&["name"])).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid document");
+    }
+
+    #[test]
+    fn set_field_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["name"], value!("Bob"));
+
+        not_wasm::call_wrap(set_field, ctx, args).unwrap();
+
+        let expected = value!({
+            "name": "Bob",
+            "tags": ["a", "b"],
+        });
+        assert_eq!(get_document(), expected);
+        assert_eq!(get_version(DATA_KEY), 1);
+    }
+
+    #[test]
+    fn set_field_creates_missing_fields() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = set_field_args(&["owner", "name"], value!("Bob"));
+
+        not_wasm::call_wrap(set_field, ctx, args).unwrap();
+
+        assert_eq!(get_document(), value!({ "owner": { "name": "Bob" } }));
+    }
+
+    #[test]
+    fn set_field_array_element() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["tags", "0"], value!("c"));
+
+        not_wasm::call_wrap(set_field, ctx, args).unwrap();
+
+        let expected = value!({
+            "name": "Alice",
+            "tags": ["c", "b"],
+        });
+        assert_eq!(get_document(), expected);
+    }
+
+    #[test]
+    fn set_field_array_element_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["tags", "2"], value!("c"));
+
+        let err = not_wasm::call_wrap(set_field, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "field not found");
+    }
+
+    #[test]
+    fn set_field_through_scalar() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["name", "first"], value!("Bob"));
+
+        let err = not_wasm::call_wrap(set_field, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid path");
+    }
+
+    #[test]
+    fn set_field_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_document(&create_document());
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = set_field_args(&["name"], value!("Bob"));
+
+        let err = not_wasm::call_wrap(set_field, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn delete_field_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+
+        not_wasm::call_wrap(delete_field, ctx, field_args(&["name"])).unwrap();
+
+        assert_eq!(get_document(), value!({ "tags": ["a", "b"] }));
+    }
+
+    #[test]
+    fn delete_field_array_element() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+
+        not_wasm::call_wrap(delete_field, ctx, field_args(&["tags", "0"])).unwrap();
+
+        let expected = value!({
+            "name": "Alice",
+            "tags": ["b"],
+        });
+        assert_eq!(get_document(), expected);
+    }
+
+    #[test]
+    fn delete_field_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+
+        let err = not_wasm::call_wrap(delete_field, ctx, field_args(&["age"])).unwrap_err();
+
+        assert_eq!(err.to_string(), "field not found");
+    }
+
+    #[test]
+    fn append_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["tags"], value!("c"));
+
+        not_wasm::call_wrap(append, ctx, args).unwrap();
+
+        let expected = value!({
+            "name": "Alice",
+            "tags": ["a", "b", "c"],
+        });
+        assert_eq!(get_document(), expected);
+    }
+
+    #[test]
+    fn append_creates_missing_array() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = set_field_args(&["tags"], value!("a"));
+
+        not_wasm::call_wrap(append, ctx, args).unwrap();
+
+        assert_eq!(get_document(), value!({ "tags": ["a"] }));
+    }
+
+    #[test]
+    fn append_not_an_array() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_document(&create_document());
+        let args = set_field_args(&["name"], value!("c"));
+
+        let err = not_wasm::call_wrap(append, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not an array");
+    }
+
+    const WRAPPED_KEY: [u8; 4] = [0xab, 0xcd, 0xef, 0x01];
+
+    fn create_envelope() -> Envelope<'static> {
+        let mut keys = BTreeMap::new();
+        keys.insert(CALLER_ID, ByteBuf::from(WRAPPED_KEY.to_vec()));
+        Envelope {
+            algorithm: "aes-256-gcm",
+            ciphertext: &[1, 2, 3],
+            keys,
+        }
+    }
+
+    fn set_envelope(envelope: &Envelope) {
+        let data = SealedData {
+            algorithm: envelope.algorithm,
+            ciphertext: envelope.ciphertext,
+        };
+        let buf = rmp_serialize(&data).unwrap();
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &buf);
+        let buf = rmp_serialize(&envelope.keys).unwrap();
+        not_wasm::set_account_data(CALLER_ID, &wrap_key(DATA_KEY), &buf);
+    }
+
+    fn get_recipients() -> Vec<String> {
+        let buf = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
+        let data: SealedData = rmp_deserialize(&buf).unwrap();
+        assert_eq!(data.ciphertext, &[1, 2, 3]);
+        let buf = not_wasm::get_account_data(CALLER_ID, &wrap_key(DATA_KEY));
+        let keys: BTreeMap<&str, ByteBuf> = rmp_deserialize(&buf).unwrap();
+        keys.keys().map(|key| key.to_string()).collect()
+    }
+
+    #[test]
+    fn store_envelope_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = StoreEnvelopeArgs {
+            key: DATA_KEY,
+            envelope: create_envelope(),
+            force: false,
+        };
+
+        not_wasm::call_wrap(store_envelope, ctx, args).unwrap();
+
+        assert_eq!(get_recipients(), vec![CALLER_ID]);
+    }
+
+    #[test]
+    fn store_envelope_without_recipients() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let mut envelope = create_envelope();
+        envelope.keys.clear();
+        let args = StoreEnvelopeArgs {
+            key: DATA_KEY,
+            envelope,
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_envelope, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid envelope");
+    }
+
+    #[test]
+    fn store_envelope_empty_wrapped_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let mut envelope = create_envelope();
+        envelope.keys.insert(GRANTEE_ID, ByteBuf::new());
+        let args = StoreEnvelopeArgs {
+            key: DATA_KEY,
+            envelope,
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_envelope, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid envelope");
+    }
+
+    #[test]
+    fn store_envelope_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = StoreEnvelopeArgs {
+            key: DATA_KEY,
+            envelope: create_envelope(),
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_envelope, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn load_envelope_granted() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_envelope(&create_envelope());
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let res = not_wasm::call_wrap(load_envelope, ctx, args).unwrap();
+
+        let expected = rmp_serialize_named(&create_envelope()).unwrap();
+        assert_eq!(res.0, expected);
+    }
+
+    #[test]
+    fn load_envelope_not_an_envelope() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        let args = LoadDataArgs { key: DATA_KEY };
+
+        let err = not_wasm::call_wrap(load_envelope, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid envelope");
+    }
+
+    #[test]
+    fn add_recipient_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let args = AddRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+            wrapped_key: &WRAPPED_KEY,
+        };
+
+        not_wasm::call_wrap(add_recipient, ctx, args).unwrap();
+
+        assert_eq!(get_recipients(), vec![GRANTEE_ID, CALLER_ID]);
+    }
+
+    #[test]
+    fn add_recipient_keeps_value() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let args = AddRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+            wrapped_key: &WRAPPED_KEY,
+        };
+
+        not_wasm::call_wrap(add_recipient, ctx, args).unwrap();
+
+        assert_eq!(load_version(DATA_KEY), 0);
+    }
+
+    #[test]
+    fn add_recipient_protected_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let buf = rmp_serialize(&vec![DATA_KEY]).unwrap();
+        not_wasm::set_account_data(CALLER_ID, PROTECTED_KEY, &buf);
+        let args = AddRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+            wrapped_key: &WRAPPED_KEY,
+        };
+
+        let err = not_wasm::call_wrap(add_recipient, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "protected key");
+    }
+
+    #[test]
+    fn remove_envelope_removes_wraps() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, &wrap_key(DATA_KEY));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn add_recipient_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_envelope(&create_envelope());
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, true, true));
+        let args = AddRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+            wrapped_key: &WRAPPED_KEY,
+        };
+
+        let err = not_wasm::call_wrap(add_recipient, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn add_recipient_envelope_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = AddRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+            wrapped_key: &WRAPPED_KEY,
+        };
+
+        let err = not_wasm::call_wrap(add_recipient, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "envelope not found");
+    }
+
+    #[test]
+    fn revoke_recipient_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let mut envelope = create_envelope();
+        envelope
+            .keys
+            .insert(GRANTEE_ID, ByteBuf::from(WRAPPED_KEY.to_vec()));
+        set_envelope(&envelope);
+        let args = RevokeRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+        };
+
+        not_wasm::call_wrap(revoke_recipient, ctx, args).unwrap();
+
+        assert_eq!(get_recipients(), vec![CALLER_ID]);
+    }
+
+    #[test]
+    fn revoke_recipient_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let args = RevokeRecipientArgs {
+            key: DATA_KEY,
+            recipient: GRANTEE_ID,
+        };
+
+        let err = not_wasm::call_wrap(revoke_recipient, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "recipient not found");
+    }
+
+    #[test]
+    fn revoke_last_recipient() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_envelope(&create_envelope());
+        let args = RevokeRecipientArgs {
+            key: DATA_KEY,
+            recipient: CALLER_ID,
+        };
+
+        let err = not_wasm::call_wrap(revoke_recipient, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "last recipient");
+    }
+
+    fn blob_data(size: usize) -> Vec<u8> {
+        (0..size).map(|i| (i % 251) as u8).collect()
+    }
+
+    fn begin_upload_args(hash: &[u8], size: usize, chunks: u32) -> BeginUploadArgs<'_> {
+        BeginUploadArgs {
+            hash,
+            size: size as u64,
+            chunks,
+        }
+    }
+
+    fn upload_blob(data: &[u8]) -> Vec<u8> {
+        let hash = trinci_sdk::sha256(data);
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let parts: Vec<&[u8]> = data.chunks(1000).collect();
+        let args = begin_upload_args(&hash, data.len(), parts.len() as u32);
+        not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+        for (index, part) in parts.into_iter().enumerate() {
+            let args = PutChunkArgs {
+                hash: &hash,
+                index: index as u32,
+                data: part,
+            };
+            not_wasm::call_wrap(put_chunk, ctx, args).unwrap();
+        }
+        not_wasm::call_wrap(commit_upload, ctx, BlobArgs { hash: &hash }).unwrap();
+        hash
+    }
+
+    #[test]
+    fn upload_blob_test() {
+        let data = blob_data(70_000);
+
+        let hash = upload_blob(&data);
+
+        let key = hash_key(BLOB_PREFIX, &hash).unwrap();
+        let buf = not_wasm::get_account_data(CALLER_ID, &key);
+        let info: BlobInfo = rmp_deserialize(&buf).unwrap();
+        assert_eq!(info.size, 70_000);
+        assert_eq!(info.chunks, 2);
+        let chunk = not_wasm::get_account_data(CALLER_ID, &chunk_key(&key, 1));
+        assert_eq!(chunk, &data[BLOB_CHUNK_SIZE as usize..]);
+        let key = hash_key(UPLOAD_PREFIX, &hash).unwrap();
+        assert!(not_wasm::get_account_data(CALLER_ID, &key).is_empty());
+        assert!(not_wasm::get_account_data(CALLER_ID, &chunk_key(&key, 0)).is_empty());
+    }
+
+    #[test]
+    fn begin_upload_of_stored_blob() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let data = blob_data(3000);
+        let hash = upload_blob(&data);
+        let args = begin_upload_args(&hash, data.len(), 3);
+
+        let res = not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+
+        assert!(!res);
+        let key = hash_key(UPLOAD_PREFIX, &hash).unwrap();
+        assert!(not_wasm::get_account_data(CALLER_ID, &key).is_empty());
+    }
+
+    #[test]
+    fn begin_upload_too_large() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1]);
+        let args = begin_upload_args(&hash, MAX_BLOB_SIZE as usize + 1, 1);
+
+        let err = not_wasm::call_wrap(begin_upload, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "blob too large");
+    }
+
+    #[test]
+    fn begin_upload_invalid_hash() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = begin_upload_args(&[1, 2, 3], 10, 1);
+
+        let err = not_wasm::call_wrap(begin_upload, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid hash");
+    }
+
+    #[test]
+    fn begin_upload_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        let hash = trinci_sdk::sha256(&[1]);
+        let args = begin_upload_args(&hash, 1, 1);
+
+        let err = not_wasm::call_wrap(begin_upload, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn put_chunk_upload_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1]);
+        let args = PutChunkArgs {
+            hash: &hash,
+            index: 0,
+            data: &[1],
+        };
+
+        let err = not_wasm::call_wrap(put_chunk, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "upload not found");
+    }
+
+    #[test]
+    fn put_chunk_invalid_index() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2]);
+        let args = begin_upload_args(&hash, 2, 2);
+        not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+        let args = PutChunkArgs {
+            hash: &hash,
+            index: 2,
+            data: &[1],
+        };
+
+        let err = not_wasm::call_wrap(put_chunk, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid chunk index");
+    }
+
+    #[test]
+    fn commit_upload_missing_chunk() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2]);
+        let args = begin_upload_args(&hash, 2, 2);
+        not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+        let args = PutChunkArgs {
+            hash: &hash,
+            index: 0,
+            data: &[1],
+        };
+        not_wasm::call_wrap(put_chunk, ctx, args).unwrap();
+
+        let err = not_wasm::call_wrap(commit_upload, ctx, BlobArgs { hash: &hash }).unwrap_err();
+
+        assert_eq!(err.to_string(), "missing chunk");
+    }
+
+    #[test]
+    fn commit_upload_hash_mismatch() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2]);
+        let args = begin_upload_args(&hash, 2, 1);
+        not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+        let args = PutChunkArgs {
+            hash: &hash,
+            index: 0,
+            data: &[2, 1],
+        };
+        not_wasm::call_wrap(put_chunk, ctx, args).unwrap();
+
+        let err = not_wasm::call_wrap(commit_upload, ctx, BlobArgs { hash: &hash }).unwrap_err();
+
+        assert_eq!(err.to_string(), "hash mismatch");
+        let key = hash_key(BLOB_PREFIX, &hash).unwrap();
+        assert!(not_wasm::get_account_data(CALLER_ID, &key).is_empty());
+    }
+
+    #[test]
+    fn blob_info_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = upload_blob(&blob_data(3000));
+
+        let buf = not_wasm::call_wrap(blob_info, ctx, BlobArgs { hash: &hash }).unwrap();
+
+        let info: BlobInfo = rmp_deserialize(&buf).unwrap();
+        assert_eq!(
+            info,
+            BlobInfo {
+                size: 3000,
+                chunk_size: BLOB_CHUNK_SIZE,
+                chunks: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn load_chunk_across_stored_chunks() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let data = blob_data(70_000);
+        let hash = upload_blob(&data);
+        let args = LoadChunkArgs {
+            hash: &hash,
+            offset: 65_000,
+            length: 2000,
+        };
+
+        let buf = not_wasm::call_wrap(load_chunk, ctx, args).unwrap();
+
+        assert_eq!(*buf, &data[65_000..67_000]);
+    }
+
+    #[test]
+    fn load_chunk_truncated_at_blob_end() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let data = blob_data(3000);
+        let hash = upload_blob(&data);
+        let args = LoadChunkArgs {
+            hash: &hash,
+            offset: 2500,
+            length: 1000,
+        };
+
+        let buf = not_wasm::call_wrap(load_chunk, ctx, args).unwrap();
+
+        assert_eq!(*buf, &data[2500..]);
+    }
+
+    #[test]
+    fn load_chunk_invalid_range() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = upload_blob(&blob_data(3000));
+        let args = LoadChunkArgs {
+            hash: &hash,
+            offset: 3001,
+            length: 1,
+        };
+
+        let err = not_wasm::call_wrap(load_chunk, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid range");
+    }
+
+    #[test]
+    fn load_chunk_blob_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1]);
+        let args = LoadChunkArgs {
+            hash: &hash,
+            offset: 0,
+            length: 1,
+        };
+
+        let err = not_wasm::call_wrap(load_chunk, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "blob not found");
+    }
+
+    #[test]
+    fn store_data_on_blob_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = StoreDataArgs {
+            key: "__sys/blob:data",
+            data: &[1, 2, 3],
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    fn quota(max_keys: u64, max_value_size: u64, max_total_size: u64) -> Quota {
+        Quota {
+            max_keys,
+            max_value_size,
+            max_total_size,
+        }
+    }
+
+    fn get_usage() -> Usage {
+        let buf = not_wasm::get_account_data(CALLER_ID, USAGE_KEY);
+        rmp_deserialize(&buf).unwrap()
+    }
+
+    fn store_data_args<'a>(key: &'a str, data: &'a [u8], force: bool) -> StoreDataArgs<'a> {
+        StoreDataArgs { key, data, force }
+    }
+
+    #[test]
+    fn set_quota_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::set_account_data(CALLER_ID, "mydata", &[1]);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, false, false));
+
+        not_wasm::call_wrap(set_quota, ctx, quota(10, 10, 100)).unwrap();
+
+        let expected = Usage {
+            keys: 2,
+            total_size: 4,
+        };
+        assert_eq!(get_usage(), expected);
+    }
+
+    #[test]
+    fn set_quota_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+
+        let err = not_wasm::call_wrap(set_quota, ctx, quota(10, 10, 100)).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn get_quota_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::call_wrap(set_quota, ctx, quota(10, 10, 100)).unwrap();
+        let args = store_data_args(DATA_KEY, &[1, 2, 3], false);
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let buf = not_wasm::call_wrap(get_quota, ctx, PackedValue::default()).unwrap();
+
+        let status: QuotaStatus = rmp_deserialize(&buf).unwrap();
+        let expected = QuotaStatus {
+            quota: quota(10, 10, 100),
+            usage: Usage {
+                keys: 1,
+                total_size: 3,
+            },
+        };
+        assert_eq!(status, expected);
+    }
+
+    #[test]
+    fn store_data_value_too_large() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::call_wrap(set_quota, ctx, quota(0, 2, 0)).unwrap();
+        let args = store_data_args(DATA_KEY, &[1, 2, 3], false);
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "value too large");
+    }
+
+    #[test]
+    fn store_data_too_many_keys() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::call_wrap(set_quota, ctx, quota(1, 0, 0)).unwrap();
+        let args = store_data_args("mydata", &[1], false);
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "too many keys");
+    }
+
+    #[test]
+    fn store_data_quota_exceeded() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::call_wrap(set_quota, ctx, quota(0, 0, 4)).unwrap();
+        let args = store_data_args(DATA_KEY, &[1, 2, 3, 4, 5], false);
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "quota exceeded");
+    }
+
+    #[test]
+    fn store_data_shrinking_over_quota() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::call_wrap(set_quota, ctx, quota(0, 0, 1)).unwrap();
+        let args = store_data_args(DATA_KEY, &[1, 2], false);
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let expected = Usage {
+            keys: 1,
+            total_size: 2,
+        };
+        assert_eq!(get_usage(), expected);
+    }
+
+    #[test]
+    fn remove_data_updates_usage() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::set_account_data(CALLER_ID, "mydata", &[1]);
+        not_wasm::call_wrap(set_quota, ctx, quota(10, 10, 100)).unwrap();
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        let expected = Usage {
+            keys: 1,
+            total_size: 1,
+        };
+        assert_eq!(get_usage(), expected);
+    }
+
+    #[test]
+    fn put_chunk_quota_exceeded() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::call_wrap(set_quota, ctx, quota(0, 0, 1000)).unwrap();
+        let data = blob_data(2000);
+        let hash = trinci_sdk::sha256(&data);
+        let args = begin_upload_args(&hash, data.len(), 1);
+        not_wasm::call_wrap(begin_upload, ctx, args).unwrap();
+        let args = PutChunkArgs {
+            hash: &hash,
+            index: 0,
+            data: &data,
+        };
+
+        let err = not_wasm::call_wrap(put_chunk, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "quota exceeded");
+    }
+
+    #[test]
+    fn commit_upload_moves_usage() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        // The quota fits the blob only once
+        not_wasm::call_wrap(set_quota, ctx, quota(0, 0, 100_000)).unwrap();
+        let data = blob_data(70_000);
+
+        let hash = upload_blob(&data);
+
+        let key = hash_key(BLOB_PREFIX, &hash).unwrap();
+        let info = not_wasm::get_account_data(CALLER_ID, &key);
+        let expected = Usage {
+            keys: 3,
+            total_size: 70_000 + info.len() as u64,
+        };
+        assert_eq!(get_usage(), expected);
+    }
+
+    #[test]
+    fn notarize_quota_exceeded() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
+        not_wasm::call_wrap(set_quota, ctx, quota(1, 0, 0)).unwrap();
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+
+        let err = not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap_err();
+
+        assert_eq!(err.to_string(), "too many keys");
+    }
+
+    #[test]
+    fn store_data_on_protected_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = store_data_args("config", &[1, 2, 3], false);
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "protected key");
+    }
+
+    #[test]
+    fn store_data_on_protected_key_with_force() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = store_data_args("config", &[1, 2, 3], true);
+
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+
+        let data = not_wasm::get_account_data(CALLER_ID, "config");
+        assert_eq!(data, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn store_data_on_protected_key_with_force_by_grantee() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant("config", GRANTEE_ID, permissions(true, true, true));
+        let args = store_data_args("config", &[1, 2, 3], true);
+
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "protected key");
+    }
+
+    #[test]
+    fn remove_data_not_existing_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = RemoveDataArgs {
+            key: DATA_KEY,
+            force: false,
+        };
+
+        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, &version_key(DATA_KEY));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn remove_data_on_protected_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::set_account_data(CALLER_ID, "init", &[1]);
+        let args = RemoveDataArgs {
+            key: "init",
+            force: false,
+        };
+
+        let err = not_wasm::call_wrap(remove_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "protected key");
+    }
+
+    #[test]
+    fn set_protected_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = SetProtectedArgs {
+            patterns: vec!["asset:*"],
+        };
+        not_wasm::call_wrap(set_protected, ctx, args).unwrap();
+
+        let args = store_data_args("config", &[1], false);
+        not_wasm::call_wrap(store_data, ctx, args).unwrap();
+        let args = store_data_args("asset:FCK", &[1], false);
+        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "protected key");
+    }
+
+    #[test]
+    fn set_protected_on_reserved_key() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = SetProtectedArgs {
+            patterns: vec!["__sys/cfg:*"],
+        };
+
+        let err = not_wasm::call_wrap(set_protected, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    #[test]
+    fn get_protected_default() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+
+        let buf = not_wasm::call_wrap(get_protected, ctx, PackedValue::default()).unwrap();
+
+        let patterns: Vec<String> = rmp_deserialize(&buf).unwrap();
+        assert_eq!(patterns, vec!["config", "init"]);
+    }
+
+    const NOTARY_PREFIX_ALL: &str = "__sys/notary:*";
+
+    fn notarize_args(hash: &[u8]) -> NotarizeArgs<'_> {
+        NotarizeArgs {
+            hash,
+            metadata: Some("contract.pdf"),
+        }
+    }
+
+    #[test]
+    fn notarize_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant(
+            NOTARY_PREFIX_ALL,
+            GRANTEE_ID,
+            permissions(false, true, false),
+        );
+        not_wasm::set_block_time(1000);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+
+        not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap();
+
+        let key = hash_key(NOTARY_PREFIX, &hash).unwrap();
+        let buf = not_wasm::get_account_data(CALLER_ID, &key);
+        let record: NotaryRecord = rmp_deserialize(&buf).unwrap();
+        let expected = NotaryRecord {
+            submitter: GRANTEE_ID,
+            timestamp: 1000,
+            metadata: Some("contract.pdf"),
+        };
+        assert_eq!(record, expected);
+    }
+
+    #[test]
+    fn notarize_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant(
+            NOTARY_PREFIX_ALL,
+            GRANTEE_ID,
+            permissions(true, false, true),
+        );
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+
+        let err = not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn notarize_metadata_too_large() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        let metadata = "x".repeat(MAX_NOTARY_METADATA + 1);
+        let args = NotarizeArgs {
+            hash: &hash,
+            metadata: Some(&metadata),
+        };
+
+        let err = not_wasm::call_wrap(notarize, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "metadata too large");
+    }
+
+    #[test]
+    fn grant_notarize() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = GrantArgs {
+            pattern: NOTARY_PREFIX_ALL,
+            account: GRANTEE_ID,
+            permissions: permissions(false, true, false),
+        };
+
+        not_wasm::call_wrap(grant, ctx, args).unwrap();
+
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap();
+    }
+
+    #[test]
+    fn notarize_already_notarized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap();
+
+        let err = not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap_err();
+
+        assert_eq!(err.to_string(), "already notarized");
+    }
+
+    #[test]
+    fn notarize_invalid_hash() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+
+        let err = not_wasm::call_wrap(notarize, ctx, notarize_args(&[1, 2, 3])).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid hash");
+    }
+
+    #[test]
+    fn remove_notary_record() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap();
+        let key = hash_key(NOTARY_PREFIX, &hash).unwrap();
+        let args = RemoveDataArgs {
+            key: &key,
+            force: true,
+        };
+
+        let err = not_wasm::call_wrap(remove_data, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "reserved key");
+    }
+
+    #[test]
+    fn verify_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_grant(
+            NOTARY_PREFIX_ALL,
+            GRANTEE_ID,
+            permissions(false, true, false),
+        );
+        not_wasm::set_block_time(1000);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+        not_wasm::call_wrap(notarize, ctx, notarize_args(&hash)).unwrap();
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+
+        let buf = not_wasm::call_wrap(verify, ctx, VerifyArgs { hash: &hash }).unwrap();
+
+        let record: NotaryRecord = rmp_deserialize(&buf).unwrap();
+        assert_eq!(record.submitter, GRANTEE_ID);
+        assert_eq!(record.timestamp, 1000);
+    }
+
+    #[test]
+    fn verify_not_notarized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let hash = trinci_sdk::sha256(&[1, 2, 3]);
+
+        let err = not_wasm::call_wrap(verify, ctx, VerifyArgs { hash: &hash }).unwrap_err();
+
+        assert_eq!(err.to_string(), "not notarized");
+    }
+
+    #[test]
+    fn grant_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_grant("shared:*", "DummyUser", permissions(true, false, false));
+        let args = GrantArgs {
+            pattern: "shared:*",
+            account: GRANTEE_ID,
+            permissions: permissions(true, true, false),
+        };
+
+        not_wasm::call_wrap(grant, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, "__sys/acl:shared:*");
+        let entry: AclEntry = rmp_deserialize(&buf).unwrap();
+        assert_eq!(entry.len(), 2);
+        assert_eq!(entry[GRANTEE_ID], permissions(true, true, false));
+        let buf = not_wasm::get_account_data(CALLER_ID, ACL_INDEX_KEY);
+        assert!(acl_patterns(&buf).contains("shared:*"));
     }
 
     #[test]
-    fn unauthorized_store_data() {
-        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
-
-        let args = StoreDataArgs {
-            key: DATA_KEY,
-            data: &[1, 2, 3],
+    fn grant_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        let args = GrantArgs {
+            pattern: DATA_KEY,
+            account: GRANTEE_ID,
+            permissions: permissions(true, true, true),
         };
 
-        let err = not_wasm::call_wrap(store_data, ctx, args).unwrap_err();
+        let err = not_wasm::call_wrap(grant, ctx, args).unwrap_err();
 
         assert_eq!(err.to_string(), "not authorized");
     }
 
     #[test]
-    fn load_data_test() {
+    fn grant_invalid_pattern() {
         let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
-        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
-        let args = LoadDataArgs { key: DATA_KEY };
+        let args = GrantArgs {
+            pattern: "sha*red",
+            account: GRANTEE_ID,
+            permissions: permissions(true, true, true),
+        };
 
-        let data = not_wasm::call_wrap(load_data, ctx, args).unwrap();
+        let err = not_wasm::call_wrap(grant, ctx, args).unwrap_err();
 
-        assert_eq!(*data, &[1, 2, 3]);
+        assert_eq!(err.to_string(), "invalid pattern");
     }
 
     #[test]
-    fn load_data_not_existing_key() {
+    fn grant_on_reserved_key() {
         let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
-        let args = LoadDataArgs { key: "config" };
+        let args = GrantArgs {
+            pattern: "__sys/acl:*",
+            account: GRANTEE_ID,
+            permissions: permissions(true, true, true),
+        };
 
-        let data = not_wasm::call_wrap(load_data, ctx, args).unwrap();
+        let err = not_wasm::call_wrap(grant, ctx, args).unwrap_err();
 
-        assert!(data.is_empty());
+        assert_eq!(err.to_string(), "reserved key");
     }
 
     #[test]
-    fn unauthorized_load_data() {
-        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
-        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
-        let args = LoadDataArgs { key: DATA_KEY };
+    fn revoke_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_grant(DATA_KEY, GRANTEE_ID, permissions(true, true, true));
+        let args = RevokeArgs {
+            pattern: DATA_KEY,
+            account: GRANTEE_ID,
+        };
 
-        let err = not_wasm::call_wrap(load_data, ctx, args).unwrap_err();
+        not_wasm::call_wrap(revoke, ctx, args).unwrap();
 
-        assert_eq!(err.to_string(), "not authorized");
+        let buf = not_wasm::get_account_data(CALLER_ID, "__sys/acl:data");
+        assert!(buf.is_empty());
+        let buf = not_wasm::get_account_data(CALLER_ID, ACL_INDEX_KEY);
+        assert!(!acl_patterns(&buf).contains(DATA_KEY));
     }
 
     #[test]
-    fn remove_data_test() {
+    fn revoke_not_existing_grant() {
         let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
-        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
-        let args = RemoveDataArgs { key: DATA_KEY };
+        set_grant(DATA_KEY, "DummyUser", permissions(true, true, true));
+        let args = RevokeArgs {
+            pattern: DATA_KEY,
+            account: GRANTEE_ID,
+        };
 
-        not_wasm::call_wrap(remove_data, ctx, args).unwrap();
+        let err = not_wasm::call_wrap(revoke, ctx, args).unwrap_err();
 
-        let data = not_wasm::get_account_data(CALLER_ID, DATA_KEY);
-        assert!(data.is_empty());
+        assert_eq!(err.to_string(), "grant not found");
     }
 
     #[test]
-    fn unauthorized_remove_data() {
-        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
-        not_wasm::set_account_data(CALLER_ID, DATA_KEY, &[1, 2, 3]);
-        let args = RemoveDataArgs { key: DATA_KEY };
+    fn get_acl_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_grant("shared:*", GRANTEE_ID, permissions(true, false, true));
+        let args = GetAclArgs {
+            pattern: "shared:*",
+        };
 
-        let err = not_wasm::call_wrap(remove_data, ctx, args).unwrap_err();
+        let buf = not_wasm::call_wrap(get_acl, ctx, args).unwrap();
 
-        assert_eq!(err.to_string(), "not authorized");
+        let entry: AclEntry = rmp_deserialize(&buf).unwrap();
+        assert_eq!(entry[GRANTEE_ID], permissions(true, false, true));
     }
 
     #[test]
@@ -271,4 +3495,525 @@ mod tests {
 
         assert_eq!(err.to_string(), "not authorized");
     }
+
+    const OTHER_ASSET_ID: &str = "QmOtherAssetS316M4yVmxdxPB6XN63ob2LjFYkP6MLq";
+
+    fn set_assets() {
+        not_wasm::set_account_asset_gen(CALLER_ID, ASSET_ID, Asset::new(9));
+        not_wasm::set_account_asset_gen(CALLER_ID, OTHER_ASSET_ID, Asset::new(4));
+        for asset in [ASSET_ID, OTHER_ASSET_ID] {
+            not_wasm::set_contract_method(asset, "balance", not_wasm::asset_balance);
+            not_wasm::set_contract_method(asset, "transfer", not_wasm::asset_transfer);
+        }
+    }
+
+    #[test]
+    fn balances_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        let args = BalancesArgs {
+            assets: vec![OTHER_ASSET_ID, ASSET_ID],
+        };
+
+        let units = not_wasm::call_wrap(balances, ctx, args).unwrap();
+
+        assert_eq!(units, vec![4, 9]);
+    }
+
+    #[test]
+    fn balances_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        set_assets();
+        let args = BalancesArgs {
+            assets: vec![ASSET_ID],
+        };
+
+        let err = not_wasm::call_wrap(balances, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    fn create_multi_transfer_args() -> MultiTransferArgs<'static> {
+        MultiTransferArgs {
+            transfers: vec![
+                TransferArgs {
+                    to: "abcdef",
+                    asset: ASSET_ID,
+                    units: 3,
+                },
+                TransferArgs {
+                    to: "ghijkl",
+                    asset: OTHER_ASSET_ID,
+                    units: 4,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn multi_transfer_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        let args = create_multi_transfer_args();
+
+        let buf = not_wasm::call_wrap(multi_transfer, ctx, args).unwrap();
+
+        let results: Vec<TransferResult> = rmp_deserialize(&buf).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].balance, 6);
+        assert_eq!(results[1].to, "ghijkl");
+        assert_eq!(results[1].balance, 0);
+        let asset: Asset = not_wasm::get_account_asset_gen("ghijkl", OTHER_ASSET_ID);
+        assert_eq!(asset.units, 4);
+    }
+
+    #[test]
+    fn multi_transfer_failed_transfer() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        let mut args = create_multi_transfer_args();
+        args.transfers[1].units = 5;
+
+        let err = not_wasm::call_wrap(multi_transfer, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "transfer 1 failed: error during transfer");
+    }
+
+    #[test]
+    fn multi_transfer_empty() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = MultiTransferArgs { transfers: vec![] };
+
+        let err = not_wasm::call_wrap(multi_transfer, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "invalid arguments");
+    }
+
+    #[test]
+    fn multi_transfer_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, "DummyUser");
+        set_assets();
+        let args = create_multi_transfer_args();
+
+        let err = not_wasm::call_wrap(multi_transfer, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    fn add_order_args(start: u64, interval: u64) -> AddOrderArgs<'static> {
+        AddOrderArgs {
+            id: "rent",
+            to: "abcdef",
+            asset: ASSET_ID,
+            units: 3,
+            reward: 1,
+            start,
+            interval,
+        }
+    }
+
+    #[test]
+    fn add_order_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 3600)).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, &order_key("rent"));
+        let order: Order = rmp_deserialize(&buf).unwrap();
+        assert_eq!(order.next, 1000);
+        assert_eq!(order.executions, 0);
+        assert!(order.active);
+    }
+
+    #[test]
+    fn add_order_already_exists() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 3600)).unwrap();
+
+        let err = not_wasm::call_wrap(add_order, ctx, add_order_args(0, 0)).unwrap_err();
+
+        assert_eq!(err.to_string(), "order already exists");
+    }
+
+    #[test]
+    fn add_order_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+
+        let err = not_wasm::call_wrap(add_order, ctx, add_order_args(0, 0)).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn remove_order_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        not_wasm::call_wrap(add_order, ctx, add_order_args(0, 0)).unwrap();
+
+        not_wasm::call_wrap(remove_order, ctx, OrderArgs { id: "rent" }).unwrap();
+
+        assert!(not_wasm::get_account_data(CALLER_ID, &order_key("rent")).is_empty());
+    }
+
+    #[test]
+    fn remove_order_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+
+        let err = not_wasm::call_wrap(remove_order, ctx, OrderArgs { id: "rent" }).unwrap_err();
+
+        assert_eq!(err.to_string(), "order not found");
+    }
+
+    #[test]
+    fn execute_due_periodic_order() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 3600)).unwrap();
+        not_wasm::set_block_time(2000);
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(executed, vec!["rent"]);
+        let buf = not_wasm::get_account_data(CALLER_ID, &order_key("rent"));
+        let order: Order = rmp_deserialize(&buf).unwrap();
+        assert_eq!(order.next, 4600);
+        assert_eq!(order.executions, 1);
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", ASSET_ID);
+        assert_eq!(asset.units, 3);
+        let asset: Asset = not_wasm::get_account_asset_gen(GRANTEE_ID, ASSET_ID);
+        assert_eq!(asset.units, 1);
+        let asset: Asset = not_wasm::get_account_asset_gen(CALLER_ID, ASSET_ID);
+        assert_eq!(asset.units, 5);
+    }
+
+    #[test]
+    fn execute_due_twice() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 3600)).unwrap();
+        not_wasm::set_block_time(2000);
+        not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert!(executed.is_empty());
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", ASSET_ID);
+        assert_eq!(asset.units, 3);
+    }
+
+    #[test]
+    fn execute_due_single_order() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 0)).unwrap();
+        not_wasm::set_block_time(10_000);
+        not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert!(executed.is_empty());
+        let buf = not_wasm::get_account_data(CALLER_ID, &order_key("rent"));
+        let order: Order = rmp_deserialize(&buf).unwrap();
+        assert!(!order.active);
+        assert_eq!(order.executions, 1);
+    }
+
+    #[test]
+    fn execute_due_order_not_due() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        not_wasm::call_wrap(add_order, ctx, add_order_args(1000, 0)).unwrap();
+        not_wasm::set_block_time(999);
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert!(executed.is_empty());
+    }
+
+    #[test]
+    fn execute_due_insufficient_funds() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        let mut args = add_order_args(0, 0);
+        args.units = 10;
+        not_wasm::call_wrap(add_order, ctx, args).unwrap();
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert!(executed.is_empty());
+        let buf = not_wasm::get_account_data(CALLER_ID, &order_key("rent"));
+        let order: Order = rmp_deserialize(&buf).unwrap();
+        assert!(order.active);
+        assert_eq!(order.executions, 0);
+    }
+
+    #[test]
+    fn execute_due_unpayable_reward() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_assets();
+        // The first order transfer fits the balance but its reward does not
+        let mut args = add_order_args(0, 0);
+        args.id = "a-rent";
+        args.units = 9;
+        not_wasm::call_wrap(add_order, ctx, args).unwrap();
+        let mut args = add_order_args(0, 0);
+        args.id = "b-rent";
+        args.units = 2;
+        not_wasm::call_wrap(add_order, ctx, args).unwrap();
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+
+        let executed = not_wasm::call_wrap(execute_due, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(executed, vec!["b-rent"]);
+        let buf = not_wasm::get_account_data(CALLER_ID, &order_key("a-rent"));
+        let order: Order = rmp_deserialize(&buf).unwrap();
+        assert!(order.active);
+        assert_eq!(order.executions, 0);
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", ASSET_ID);
+        assert_eq!(asset.units, 2);
+        let asset: Asset = not_wasm::get_account_asset_gen(GRANTEE_ID, ASSET_ID);
+        assert_eq!(asset.units, 1);
+        let asset: Asset = not_wasm::get_account_asset_gen(CALLER_ID, ASSET_ID);
+        assert_eq!(asset.units, 6);
+    }
+
+    fn set_operator_data(destinations: Vec<&str>) {
+        set_operator_limit(
+            SpendingLimit {
+                units: 5,
+                period: 3600,
+            },
+            destinations,
+        );
+    }
+
+    fn set_operator_limit(limit: SpendingLimit, destinations: Vec<&str>) {
+        let mut limits = BTreeMap::new();
+        limits.insert(ASSET_ID, limit);
+        let operator = Operator {
+            limits,
+            destinations,
+            spent: BTreeMap::new(),
+        };
+        let buf = rmp_serialize(&operator).unwrap();
+        not_wasm::set_account_data(CALLER_ID, &operator_key(GRANTEE_ID), &buf);
+    }
+
+    fn operator_transfer_args(units: u64) -> TransferArgs<'static> {
+        TransferArgs {
+            to: "abcdef",
+            asset: ASSET_ID,
+            units,
+        }
+    }
+
+    #[test]
+    fn set_operator_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let mut limits = BTreeMap::new();
+        limits.insert(
+            ASSET_ID,
+            SpendingLimit {
+                units: 5,
+                period: 3600,
+            },
+        );
+        let args = SetOperatorArgs {
+            account: GRANTEE_ID,
+            limits,
+            destinations: vec![],
+        };
+
+        not_wasm::call_wrap(set_operator, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, &operator_key(GRANTEE_ID));
+        let operator: Operator = rmp_deserialize(&buf).unwrap();
+        assert_eq!(operator.limits[ASSET_ID].units, 5);
+        assert!(operator.spent.is_empty());
+    }
+
+    #[test]
+    fn set_operator_not_authorized() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        let args = SetOperatorArgs {
+            account: GRANTEE_ID,
+            limits: BTreeMap::new(),
+            destinations: vec![],
+        };
+
+        let err = not_wasm::call_wrap(set_operator, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "not authorized");
+    }
+
+    #[test]
+    fn revoke_operator_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        set_operator_data(vec![]);
+        let args = OperatorArgs {
+            account: GRANTEE_ID,
+        };
+
+        not_wasm::call_wrap(revoke_operator, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_data(CALLER_ID, &operator_key(GRANTEE_ID));
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn revoke_operator_not_found() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = OperatorArgs {
+            account: GRANTEE_ID,
+        };
+
+        let err = not_wasm::call_wrap(revoke_operator, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "operator not found");
+    }
+
+    #[test]
+    fn operator_transfer() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec!["abcdef"]);
+        not_wasm::set_block_time(1000);
+
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap();
+
+        let asset: Asset = not_wasm::get_account_asset_gen(CALLER_ID, ASSET_ID);
+        assert_eq!(asset.units, 6);
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", ASSET_ID);
+        assert_eq!(asset.units, 3);
+        let buf = not_wasm::get_account_data(CALLER_ID, &spend_key(0));
+        let record: SpendRecord = rmp_deserialize(&buf).unwrap();
+        let expected = SpendRecord {
+            operator: GRANTEE_ID,
+            to: "abcdef",
+            asset: ASSET_ID,
+            units: 3,
+            timestamp: 1000,
+        };
+        assert_eq!(record, expected);
+    }
+
+    #[test]
+    fn operator_transfer_over_limit() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec![]);
+        not_wasm::set_block_time(1000);
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap();
+
+        let err = not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap_err();
+
+        assert_eq!(err.to_string(), "spending limit exceeded");
+    }
+
+    #[test]
+    fn operator_transfer_next_period() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec![]);
+        not_wasm::set_block_time(1000);
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap();
+        not_wasm::set_block_time(4600);
+
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap();
+
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", ASSET_ID);
+        assert_eq!(asset.units, 6);
+        let buf = not_wasm::get_account_data(CALLER_ID, SPEND_COUNT_KEY);
+        assert_eq!(rmp_deserialize::<u64>(&buf).unwrap(), 2);
+    }
+
+    #[test]
+    fn operator_transfer_lifetime_limit() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        let limit = SpendingLimit {
+            units: 5,
+            period: 0,
+        };
+        set_operator_limit(limit, vec![]);
+        not_wasm::set_block_time(1000);
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap();
+        not_wasm::set_block_time(u64::MAX);
+
+        let err = not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap_err();
+
+        assert_eq!(err.to_string(), "spending limit exceeded");
+    }
+
+    #[test]
+    fn operator_transfer_spent_overflow() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        let limit = SpendingLimit {
+            units: u64::MAX,
+            period: 0,
+        };
+        let mut spent = BTreeMap::new();
+        spent.insert(
+            ASSET_ID,
+            Spent {
+                since: 0,
+                units: u64::MAX - 1,
+            },
+        );
+        let mut limits = BTreeMap::new();
+        limits.insert(ASSET_ID, limit);
+        let operator = Operator {
+            limits,
+            destinations: vec![],
+            spent,
+        };
+        let buf = rmp_serialize(&operator).unwrap();
+        not_wasm::set_account_data(CALLER_ID, &operator_key(GRANTEE_ID), &buf);
+
+        let err = not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap_err();
+
+        assert_eq!(err.to_string(), "spent units overflow");
+    }
+
+    #[test]
+    fn operator_transfer_destination_not_allowed() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec!["ghijkl"]);
+
+        let err = not_wasm::call_wrap(transfer, ctx, operator_transfer_args(3)).unwrap_err();
+
+        assert_eq!(err.to_string(), "destination not allowed");
+    }
+
+    #[test]
+    fn operator_transfer_asset_not_allowed() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec![]);
+        let mut args = operator_transfer_args(3);
+        args.asset = OTHER_ASSET_ID;
+
+        let err = not_wasm::call_wrap(transfer, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "asset not allowed");
+    }
+
+    #[test]
+    fn get_spends_test() {
+        let ctx = not_wasm::create_app_context(CALLER_ID, GRANTEE_ID);
+        set_assets();
+        set_operator_data(vec![]);
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(1)).unwrap();
+        not_wasm::call_wrap(transfer, ctx, operator_transfer_args(2)).unwrap();
+        let ctx = not_wasm::create_app_context(CALLER_ID, CALLER_ID);
+        let args = GetSpendsArgs { start: 1, limit: 0 };
+
+        let buf = not_wasm::call_wrap(get_spends, ctx, args).unwrap();
+
+        let records: Vec<SpendRecord> = rmp_deserialize(&buf).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].units, 2);
+    }
 }
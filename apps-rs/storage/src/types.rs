@@ -15,7 +15,11 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
 
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use trinci_sdk::Value;
 
 /// Load data arguments.
 #[derive(Serialize, Deserialize)]
@@ -31,6 +35,9 @@ pub struct LoadDataArgs<'a> {
 pub struct RemoveDataArgs<'a> {
     /// Location to delete the data
     pub key: &'a str,
+    /// Allow the owner to delete a protected key
+    #[serde(default)]
+    pub force: bool,
 }
 
 /// Store data arguments.
@@ -42,6 +49,33 @@ pub struct StoreDataArgs<'a> {
     /// Data to save
     #[serde(with = "serde_bytes")]
     pub data: &'a [u8],
+    /// Allow the owner to overwrite a protected key
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Store data if version arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct StoreDataIfVersionArgs<'a> {
+    /// Location to save the data to
+    pub key: &'a str,
+    /// Data to save
+    #[serde(with = "serde_bytes")]
+    pub data: &'a [u8],
+    /// Version that the current value shall have (0 if never written)
+    pub expected_version: u64,
+}
+
+/// Data with its version.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct VersionedData<'a> {
+    /// Version of the value (0 if never written)
+    pub version: u64,
+    /// Stored data
+    #[serde(with = "serde_bytes")]
+    pub data: &'a [u8],
 }
 
 /// TAI transfer arguments.
@@ -63,14 +97,565 @@ pub struct BalanceArgs<'a> {
     pub asset: &'a str,
 }
 
+/// Balances arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BalancesArgs<'a> {
+    /// Assets to get the balance of
+    #[serde(borrow)]
+    pub assets: Vec<&'a str>,
+}
+
+/// Multi transfer arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct MultiTransferArgs<'a> {
+    /// Transfers to execute, in order
+    #[serde(borrow)]
+    pub transfers: Vec<TransferArgs<'a>>,
+}
+
+/// Result of a single transfer of a multi transfer.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct TransferResult<'a> {
+    /// Destination account-id
+    pub to: &'a str,
+    /// Transferred asset
+    pub asset: &'a str,
+    /// Transferred amount
+    pub units: u64,
+    /// Account balance of the asset after the transfer
+    pub balance: u64,
+}
+
+/// Operator spending limit for an asset.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq, Default))]
+pub struct SpendingLimit {
+    /// Maximum amount spent in a period
+    pub units: u64,
+    /// Period duration in block time (0 for a lifetime limit that never resets)
+    pub period: u64,
+}
+
+/// Amount spent by an operator in the current period.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Spent {
+    /// Block time of the period start
+    pub since: u64,
+    /// Amount spent since the period start
+    pub units: u64,
+}
+
+/// Set operator arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct SetOperatorArgs<'a> {
+    /// Operator account-id
+    pub account: &'a str,
+    /// Spending limit of each asset the operator can transfer
+    #[serde(borrow)]
+    pub limits: BTreeMap<&'a str, SpendingLimit>,
+    /// Accounts the operator can transfer to (empty for any account)
+    #[serde(borrow)]
+    pub destinations: Vec<&'a str>,
+}
+
+/// Arguments of the methods working on a single operator.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct OperatorArgs<'a> {
+    /// Operator account-id
+    pub account: &'a str,
+}
+
+/// Operator stored in the account data.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Operator<'a> {
+    /// Spending limit of each asset the operator can transfer
+    #[serde(borrow)]
+    pub limits: BTreeMap<&'a str, SpendingLimit>,
+    /// Accounts the operator can transfer to (empty for any account)
+    #[serde(borrow)]
+    pub destinations: Vec<&'a str>,
+    /// Amount spent for each asset in the current period (dynamic)
+    #[serde(borrow)]
+    pub spent: BTreeMap<&'a str, Spent>,
+}
+
+/// Operator spend log record.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct SpendRecord<'a> {
+    /// Operator account-id
+    pub operator: &'a str,
+    /// Destination account-id
+    pub to: &'a str,
+    /// Transferred asset
+    pub asset: &'a str,
+    /// Transferred amount
+    pub units: u64,
+    /// Block time of the transfer
+    pub timestamp: u64,
+}
+
+/// Get spends arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct GetSpendsArgs {
+    /// Position of the first record to return
+    pub start: u64,
+    /// Maximum number of records to return (0 for the default page size)
+    pub limit: u32,
+}
+
+/// Add order arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct AddOrderArgs<'a> {
+    /// Order identifier
+    pub id: &'a str,
+    /// Destination account-id
+    pub to: &'a str,
+    /// Asset to transfer
+    pub asset: &'a str,
+    /// Amount to transfer at each execution
+    pub units: u64,
+    /// Amount of the same asset paid to the account executing the order
+    pub reward: u64,
+    /// Block time from which the order is due
+    pub start: u64,
+    /// Block time between two executions (0 for a single execution)
+    pub interval: u64,
+}
+
+/// Arguments of the methods working on a single order.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct OrderArgs<'a> {
+    /// Order identifier
+    pub id: &'a str,
+}
+
+/// Standing order stored in the account data.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Order<'a> {
+    /// Destination account-id
+    pub to: &'a str,
+    /// Asset to transfer
+    pub asset: &'a str,
+    /// Amount to transfer at each execution
+    pub units: u64,
+    /// Amount of the same asset paid to the account executing the order
+    pub reward: u64,
+    /// Block time between two executions (0 for a single execution)
+    pub interval: u64,
+    /// Block time from which the next execution is due
+    pub next: u64,
+    /// Number of executions (dynamic)
+    pub executions: u64,
+    /// `false` once a single execution order has been executed
+    pub active: bool,
+}
+
+/// Access rights granted to an account.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[cfg_attr(test, derive(Debug, PartialEq, Default))]
+pub struct Permissions {
+    /// Allowed to load the data
+    pub read: bool,
+    /// Allowed to store the data
+    pub write: bool,
+    /// Allowed to remove the data
+    pub delete: bool,
+}
+
+/// Access control list of a key or of a key prefix.
+///
+/// Maps the account-id of the grantee (a user or a contract account)
+/// to its permissions.
+pub type AclEntry<'a> = BTreeMap<&'a str, Permissions>;
+
+/// Grant arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct GrantArgs<'a> {
+    /// Key or key prefix (terminated by `*`) to grant access to
+    pub pattern: &'a str,
+    /// Grantee account-id
+    pub account: &'a str,
+    /// Granted permissions
+    pub permissions: Permissions,
+}
+
+/// Revoke arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct RevokeArgs<'a> {
+    /// Key or key prefix (terminated by `*`) to revoke access to
+    pub pattern: &'a str,
+    /// Grantee account-id
+    pub account: &'a str,
+}
+
+/// Get ACL arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct GetAclArgs<'a> {
+    /// Key or key prefix (terminated by `*`)
+    pub pattern: &'a str,
+}
+
+/// List keys arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct ListKeysArgs<'a> {
+    /// Prefix of the keys to list (empty to list all the keys)
+    pub prefix: &'a str,
+    /// Last key returned by the previous page
+    #[serde(default)]
+    pub cursor: Option<&'a str>,
+    /// Maximum number of keys to return (0 for the default page size)
+    #[serde(default)]
+    pub limit: u32,
+}
+
+/// List keys result.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct ListKeysResult {
+    /// Keys of the page, in lexicographic order
+    pub keys: Vec<String>,
+    /// Cursor to get the next page, `None` on the last page
+    pub cursor: Option<String>,
+}
+
+/// Load many arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct LoadManyArgs<'a> {
+    /// Locations to retrieve the data from
+    #[serde(borrow)]
+    pub keys: Vec<&'a str>,
+}
+
+/// Batch operation.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BatchOp<'a> {
+    /// Operation kind: "put" or "remove"
+    pub op: &'a str,
+    /// Location of the data
+    pub key: &'a str,
+    /// Data to save (ignored by "remove")
+    #[serde(with = "serde_bytes", default)]
+    pub data: &'a [u8],
+}
+
+/// Batch precondition.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Precondition<'a> {
+    /// Location of the data
+    pub key: &'a str,
+    /// Expected sha256 of the current data (empty if the key shall not exist)
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+}
+
+/// Batch arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BatchArgs<'a> {
+    /// Operations to apply, in order
+    #[serde(borrow)]
+    pub ops: Vec<BatchOp<'a>>,
+    /// Conditions to be satisfied before applying the operations
+    #[serde(borrow, default)]
+    pub preconditions: Vec<Precondition<'a>>,
+}
+
+/// Notarize arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct NotarizeArgs<'a> {
+    /// Sha256 of the notarized document
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+    /// Optional information about the document
+    pub metadata: Option<&'a str>,
+}
+
+/// Verify arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct VerifyArgs<'a> {
+    /// Sha256 of the notarized document
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+}
+
+/// Notarization record stored in the account data.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct NotaryRecord<'a> {
+    /// Account that notarized the document
+    pub submitter: &'a str,
+    /// Block time of the notarization
+    pub timestamp: u64,
+    /// Optional information about the document
+    pub metadata: Option<&'a str>,
+}
+
+/// Account data quota, a zero limit is not enforced.
+#[derive(Serialize, Deserialize, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct Quota {
+    /// Maximum number of data keys
+    pub max_keys: u64,
+    /// Maximum size of a single value in bytes
+    pub max_value_size: u64,
+    /// Maximum size of all the values in bytes
+    pub max_total_size: u64,
+}
+
+/// Account data usage, tracked while a quota is set.
+#[derive(Serialize, Deserialize, Default)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct Usage {
+    /// Number of data keys
+    pub keys: u64,
+    /// Size of all the values in bytes
+    pub total_size: u64,
+}
+
+/// Get quota result.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct QuotaStatus {
+    /// Current quota (all zeros if not set)
+    pub quota: Quota,
+    /// Current usage
+    pub usage: Usage,
+}
+
+/// Set protected arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct SetProtectedArgs<'a> {
+    /// Keys or key prefixes (terminated by `*`) that can be modified only with `force`
+    #[serde(borrow)]
+    pub patterns: Vec<&'a str>,
+}
+
+/// Arguments of the methods reading or removing a document field.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct FieldArgs<'a> {
+    /// Location of the document
+    pub key: &'a str,
+    /// Map fields and array indexes leading to the field (empty for the whole document)
+    #[serde(borrow)]
+    pub path: Vec<&'a str>,
+}
+
+/// Arguments of the methods writing a document field.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone))]
+pub struct SetFieldArgs<'a> {
+    /// Location of the document
+    pub key: &'a str,
+    /// Map fields and array indexes leading to the field (empty for the whole document)
+    #[serde(borrow)]
+    pub path: Vec<&'a str>,
+    /// Value to write
+    pub value: Value,
+}
+
+/// Encrypted envelope exchanged with the client.
+///
+/// The encryption is performed by the client, the content key is wrapped
+/// with the key of each recipient.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Envelope<'a> {
+    /// Identifier of the encryption algorithm
+    pub algorithm: &'a str,
+    /// Encrypted data
+    #[serde(with = "serde_bytes")]
+    pub ciphertext: &'a [u8],
+    /// Content key wrapped for each recipient account
+    #[serde(borrow)]
+    pub keys: BTreeMap<&'a str, ByteBuf>,
+}
+
+/// Encrypted data of an envelope as stored in the account data.
+///
+/// The wrapped content keys are stored apart, so that changing the
+/// recipients leaves the ciphertext untouched.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct SealedData<'a> {
+    /// Identifier of the encryption algorithm
+    pub algorithm: &'a str,
+    /// Encrypted data
+    #[serde(with = "serde_bytes")]
+    pub ciphertext: &'a [u8],
+}
+
+/// Store envelope arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct StoreEnvelopeArgs<'a> {
+    /// Location to save the envelope to
+    pub key: &'a str,
+    /// Envelope to save
+    #[serde(borrow)]
+    pub envelope: Envelope<'a>,
+    /// Allow the owner to overwrite a protected key
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Add recipient arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct AddRecipientArgs<'a> {
+    /// Location of the envelope
+    pub key: &'a str,
+    /// Recipient account
+    pub recipient: &'a str,
+    /// Content key wrapped for the recipient
+    #[serde(with = "serde_bytes")]
+    pub wrapped_key: &'a [u8],
+}
+
+/// Revoke recipient arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct RevokeRecipientArgs<'a> {
+    /// Location of the envelope
+    pub key: &'a str,
+    /// Recipient account
+    pub recipient: &'a str,
+}
+
+/// Begin upload arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BeginUploadArgs<'a> {
+    /// Expected sha256 of the blob
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+    /// Blob size in bytes
+    pub size: u64,
+    /// Number of chunks the blob is uploaded with
+    pub chunks: u32,
+}
+
+/// Put chunk arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct PutChunkArgs<'a> {
+    /// Expected sha256 of the blob
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+    /// Chunk position, starting from 0
+    pub index: u32,
+    /// Chunk data
+    #[serde(with = "serde_bytes")]
+    pub data: &'a [u8],
+}
+
+/// Arguments of the methods working on a single blob.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BlobArgs<'a> {
+    /// Sha256 of the blob
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+}
+
+/// Load chunk arguments.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct LoadChunkArgs<'a> {
+    /// Sha256 of the blob
+    #[serde(with = "serde_bytes")]
+    pub hash: &'a [u8],
+    /// Position of the first byte to load
+    pub offset: u64,
+    /// Number of bytes to load (truncated at the end of the blob)
+    pub length: u32,
+}
+
+/// Pending upload stored in the account data.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct Upload {
+    /// Blob size in bytes
+    pub size: u64,
+    /// Number of chunks the blob is uploaded with
+    pub chunks: u32,
+}
+
+/// Committed blob information.
+#[derive(Serialize, Deserialize)]
+#[cfg_attr(test, derive(Debug, PartialEq, Clone, Default))]
+pub struct BlobInfo {
+    /// Blob size in bytes
+    pub size: u64,
+    /// Size of the chunks the blob is stored with (the last one can be shorter)
+    pub chunk_size: u32,
+    /// Number of stored chunks
+    pub chunks: u32,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     const BALANCE_ARGS_HEX: &str = "91a346434b";
+    const BALANCES_ARGS_HEX: &str = "9192a346434ba3455552";
+    const SET_OPERATOR_ARGS_HEX: &str = "93a3426f6281a346434b9264ce0001518091a444617665";
+    const SPEND_RECORD_HEX: &str = "95a3426f62a444617665a346434b0acd03e8";
+    const ADD_ORDER_ARGS_HEX: &str = "97a472656e74a3426f62a346434b3201cd03e8cd0e10";
+    const ORDER_HEX: &str = "98a3426f62a346434b3201cd0e10cd11f801c3";
+    const MULTI_TRANSFER_ARGS_HEX: &str = "919293a3426f62a346434b0393a444617665a345555205";
     const LOAD_DATA_ARGS_HEX: &str = "91a464617461";
-    const STORE_DATA_ARGS_HEX: &str = "92a464617461c403010203";
-    const REMOVE_DATA_ARGS_HEX: &str = "91a66d7964617461";
+    const STORE_DATA_ARGS_HEX: &str = "93a464617461c403010203c3";
+    const STORE_DATA_ARGS_WITHOUT_FORCE_HEX: &str = "92a464617461c403010203";
+    const REMOVE_DATA_ARGS_HEX: &str = "92a66d7964617461c3";
+    const NOTARIZE_ARGS_HEX: &str = "92c402abcda3646f63";
+    const NOTARY_RECORD_HEX: &str = "93a3426f62cd03e8c0";
+    const QUOTA_HEX: &str = "930acd0100cd0400";
+    const SET_PROTECTED_ARGS_HEX: &str = "9192a6636f6e666967a761737365743a2a";
+    const GRANT_ARGS_HEX: &str = "93a87368617265643a2aa3426f6293c3c3c2";
+    const REVOKE_ARGS_HEX: &str = "92a87368617265643a2aa3426f62";
+    const GET_ACL_ARGS_HEX: &str = "91a464617461";
+    const LIST_KEYS_ARGS_HEX: &str = "93a77368617265643aa87368617265643a610a";
+    const LIST_KEYS_ARGS_PREFIX_ONLY_HEX: &str = "81a6707265666978a77368617265643a";
+    const LIST_KEYS_RESULT_HEX: &str = "9291a87368617265643a62c0";
+    const STORE_DATA_IF_VERSION_ARGS_HEX: &str = "93a464617461c40301020302";
+    const VERSIONED_DATA_HEX: &str = "9203c403010203";
+    const BATCH_ARGS_HEX: &str = "929293a3707574a464617461c40301020393a672656d6f7665a66d7964617461c4009192a464617461c402abcd";
+    const FIELD_ARGS_HEX: &str = "92a3646f6392a56974656d73a130";
+    const SET_FIELD_ARGS_HEX: &str = "93a3646f6391a46e616d65a3426f62";
+    const ENVELOPE_HEX: &str = "93ab6165732d3235362d67636dc40301020381a3426f62c402abcd";
+    const SEALED_DATA_HEX: &str = "92ab6165732d3235362d67636dc403010203";
+    const STORE_ENVELOPE_ARGS_HEX: &str =
+        "93a46461746193ab6165732d3235362d67636dc40301020381a3426f62c402abcdc2";
+    const ADD_RECIPIENT_ARGS_HEX: &str = "93a464617461a444617665c402abcd";
+    const REVOKE_RECIPIENT_ARGS_HEX: &str = "92a464617461a3426f62";
+    const BEGIN_UPLOAD_ARGS_HEX: &str = "93c402abcdcd012c03";
+    const PUT_CHUNK_ARGS_HEX: &str = "93c402abcd01c403010203";
+    const LOAD_CHUNK_ARGS_HEX: &str = "93c402abcd0a14";
+    const BLOB_INFO_HEX: &str = "93cd012ccd010002";
+    const LOAD_MANY_ARGS_HEX: &str = "9192a464617461a66d7964617461";
 
     #[test]
     fn balance_args_serialize() {
@@ -115,6 +700,7 @@ mod tests {
         let args = StoreDataArgs {
             key: "data",
             data: &[1u8, 2, 3],
+            force: true,
         };
 
         let buf = trinci_sdk::rmp_serialize(&args).unwrap();
@@ -127,6 +713,7 @@ mod tests {
         let expected = StoreDataArgs {
             key: "data",
             data: &[1u8, 2, 3],
+            force: true,
         };
         let buf = hex::decode(STORE_DATA_ARGS_HEX).unwrap();
 
@@ -135,9 +722,26 @@ mod tests {
         assert_eq!(args, expected);
     }
 
+    #[test]
+    fn store_data_args_without_force_deserialize() {
+        let expected = StoreDataArgs {
+            key: "data",
+            data: &[1u8, 2, 3],
+            force: false,
+        };
+        let buf = hex::decode(STORE_DATA_ARGS_WITHOUT_FORCE_HEX).unwrap();
+
+        let args: StoreDataArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
     #[test]
     fn remove_data_args_serialize() {
-        let args = RemoveDataArgs { key: "mydata" };
+        let args = RemoveDataArgs {
+            key: "mydata",
+            force: true,
+        };
 
         let buf = trinci_sdk::rmp_serialize(&args).unwrap();
 
@@ -146,11 +750,852 @@ mod tests {
 
     #[test]
     fn remove_data_deserialize() {
-        let expected = RemoveDataArgs { key: "mydata" };
+        let expected = RemoveDataArgs {
+            key: "mydata",
+            force: true,
+        };
         let buf = hex::decode(REMOVE_DATA_ARGS_HEX).unwrap();
 
         let args: RemoveDataArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
 
         assert_eq!(args, expected);
     }
+
+    fn create_grant_args() -> GrantArgs<'static> {
+        GrantArgs {
+            pattern: "shared:*",
+            account: "Bob",
+            permissions: Permissions {
+                read: true,
+                write: true,
+                delete: false,
+            },
+        }
+    }
+
+    #[test]
+    fn grant_args_serialize() {
+        let args = create_grant_args();
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), GRANT_ARGS_HEX);
+    }
+
+    #[test]
+    fn grant_args_deserialize() {
+        let expected = create_grant_args();
+        let buf = hex::decode(GRANT_ARGS_HEX).unwrap();
+
+        let args: GrantArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn revoke_args_serialize() {
+        let args = RevokeArgs {
+            pattern: "shared:*",
+            account: "Bob",
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), REVOKE_ARGS_HEX);
+    }
+
+    #[test]
+    fn revoke_args_deserialize() {
+        let expected = RevokeArgs {
+            pattern: "shared:*",
+            account: "Bob",
+        };
+        let buf = hex::decode(REVOKE_ARGS_HEX).unwrap();
+
+        let args: RevokeArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn get_acl_args_serialize() {
+        let args = GetAclArgs { pattern: "data" };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), GET_ACL_ARGS_HEX);
+    }
+
+    #[test]
+    fn get_acl_args_deserialize() {
+        let expected = GetAclArgs { pattern: "data" };
+        let buf = hex::decode(GET_ACL_ARGS_HEX).unwrap();
+
+        let args: GetAclArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn list_keys_args_serialize() {
+        let args = ListKeysArgs {
+            prefix: "shared:",
+            cursor: Some("shared:a"),
+            limit: 10,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), LIST_KEYS_ARGS_HEX);
+    }
+
+    #[test]
+    fn list_keys_args_deserialize() {
+        let expected = ListKeysArgs {
+            prefix: "shared:",
+            cursor: Some("shared:a"),
+            limit: 10,
+        };
+        let buf = hex::decode(LIST_KEYS_ARGS_HEX).unwrap();
+
+        let args: ListKeysArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn list_keys_args_deserialize_defaults() {
+        let expected = ListKeysArgs {
+            prefix: "shared:",
+            cursor: None,
+            limit: 0,
+        };
+        let buf = hex::decode(LIST_KEYS_ARGS_PREFIX_ONLY_HEX).unwrap();
+
+        let args: ListKeysArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn list_keys_result_serialize() {
+        let res = ListKeysResult {
+            keys: vec!["shared:b".to_string()],
+            cursor: None,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&res).unwrap();
+
+        assert_eq!(hex::encode(&buf), LIST_KEYS_RESULT_HEX);
+    }
+
+    #[test]
+    fn list_keys_result_deserialize() {
+        let expected = ListKeysResult {
+            keys: vec!["shared:b".to_string()],
+            cursor: None,
+        };
+        let buf = hex::decode(LIST_KEYS_RESULT_HEX).unwrap();
+
+        let res: ListKeysResult = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn load_many_args_serialize() {
+        let args = LoadManyArgs {
+            keys: vec!["data", "mydata"],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), LOAD_MANY_ARGS_HEX);
+    }
+
+    #[test]
+    fn load_many_args_deserialize() {
+        let expected = LoadManyArgs {
+            keys: vec!["data", "mydata"],
+        };
+        let buf = hex::decode(LOAD_MANY_ARGS_HEX).unwrap();
+
+        let args: LoadManyArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_batch_args() -> BatchArgs<'static> {
+        BatchArgs {
+            ops: vec![
+                BatchOp {
+                    op: "put",
+                    key: "data",
+                    data: &[1, 2, 3],
+                },
+                BatchOp {
+                    op: "remove",
+                    key: "mydata",
+                    data: &[],
+                },
+            ],
+            preconditions: vec![Precondition {
+                key: "data",
+                hash: &[0xab, 0xcd],
+            }],
+        }
+    }
+
+    #[test]
+    fn batch_args_serialize() {
+        let args = create_batch_args();
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), BATCH_ARGS_HEX);
+    }
+
+    #[test]
+    fn batch_args_deserialize() {
+        let expected = create_batch_args();
+        let buf = hex::decode(BATCH_ARGS_HEX).unwrap();
+
+        let args: BatchArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn store_data_if_version_args_serialize() {
+        let args = StoreDataIfVersionArgs {
+            key: "data",
+            data: &[1u8, 2, 3],
+            expected_version: 2,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), STORE_DATA_IF_VERSION_ARGS_HEX);
+    }
+
+    #[test]
+    fn store_data_if_version_args_deserialize() {
+        let expected = StoreDataIfVersionArgs {
+            key: "data",
+            data: &[1u8, 2, 3],
+            expected_version: 2,
+        };
+        let buf = hex::decode(STORE_DATA_IF_VERSION_ARGS_HEX).unwrap();
+
+        let args: StoreDataIfVersionArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn versioned_data_serialize() {
+        let value = VersionedData {
+            version: 3,
+            data: &[1u8, 2, 3],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&value).unwrap();
+
+        assert_eq!(hex::encode(&buf), VERSIONED_DATA_HEX);
+    }
+
+    #[test]
+    fn versioned_data_deserialize() {
+        let expected = VersionedData {
+            version: 3,
+            data: &[1u8, 2, 3],
+        };
+        let buf = hex::decode(VERSIONED_DATA_HEX).unwrap();
+
+        let value: VersionedData = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn begin_upload_args_serialize() {
+        let args = BeginUploadArgs {
+            hash: &[0xab, 0xcd],
+            size: 300,
+            chunks: 3,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), BEGIN_UPLOAD_ARGS_HEX);
+    }
+
+    #[test]
+    fn begin_upload_args_deserialize() {
+        let expected = BeginUploadArgs {
+            hash: &[0xab, 0xcd],
+            size: 300,
+            chunks: 3,
+        };
+        let buf = hex::decode(BEGIN_UPLOAD_ARGS_HEX).unwrap();
+
+        let args: BeginUploadArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn put_chunk_args_serialize() {
+        let args = PutChunkArgs {
+            hash: &[0xab, 0xcd],
+            index: 1,
+            data: &[1u8, 2, 3],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), PUT_CHUNK_ARGS_HEX);
+    }
+
+    #[test]
+    fn put_chunk_args_deserialize() {
+        let expected = PutChunkArgs {
+            hash: &[0xab, 0xcd],
+            index: 1,
+            data: &[1u8, 2, 3],
+        };
+        let buf = hex::decode(PUT_CHUNK_ARGS_HEX).unwrap();
+
+        let args: PutChunkArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn load_chunk_args_serialize() {
+        let args = LoadChunkArgs {
+            hash: &[0xab, 0xcd],
+            offset: 10,
+            length: 20,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), LOAD_CHUNK_ARGS_HEX);
+    }
+
+    #[test]
+    fn load_chunk_args_deserialize() {
+        let expected = LoadChunkArgs {
+            hash: &[0xab, 0xcd],
+            offset: 10,
+            length: 20,
+        };
+        let buf = hex::decode(LOAD_CHUNK_ARGS_HEX).unwrap();
+
+        let args: LoadChunkArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn blob_info_serialize() {
+        let info = BlobInfo {
+            size: 300,
+            chunk_size: 256,
+            chunks: 2,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&info).unwrap();
+
+        assert_eq!(hex::encode(&buf), BLOB_INFO_HEX);
+    }
+
+    #[test]
+    fn blob_info_deserialize() {
+        let expected = BlobInfo {
+            size: 300,
+            chunk_size: 256,
+            chunks: 2,
+        };
+        let buf = hex::decode(BLOB_INFO_HEX).unwrap();
+
+        let info: BlobInfo = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(info, expected);
+    }
+
+    #[test]
+    fn field_args_serialize() {
+        let args = FieldArgs {
+            key: "doc",
+            path: vec!["items", "0"],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), FIELD_ARGS_HEX);
+    }
+
+    #[test]
+    fn field_args_deserialize() {
+        let expected = FieldArgs {
+            key: "doc",
+            path: vec!["items", "0"],
+        };
+        let buf = hex::decode(FIELD_ARGS_HEX).unwrap();
+
+        let args: FieldArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_envelope() -> Envelope<'static> {
+        let mut keys = BTreeMap::new();
+        keys.insert("Bob", ByteBuf::from(vec![0xab, 0xcd]));
+        Envelope {
+            algorithm: "aes-256-gcm",
+            ciphertext: &[1, 2, 3],
+            keys,
+        }
+    }
+
+    #[test]
+    fn envelope_serialize() {
+        let envelope = create_envelope();
+
+        let buf = trinci_sdk::rmp_serialize(&envelope).unwrap();
+
+        assert_eq!(hex::encode(&buf), ENVELOPE_HEX);
+    }
+
+    #[test]
+    fn envelope_deserialize() {
+        let expected = create_envelope();
+        let buf = hex::decode(ENVELOPE_HEX).unwrap();
+
+        let envelope: Envelope = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(envelope, expected);
+    }
+
+    fn create_sealed_data() -> SealedData<'static> {
+        SealedData {
+            algorithm: "aes-256-gcm",
+            ciphertext: &[1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn sealed_data_serialize() {
+        let data = create_sealed_data();
+
+        let buf = trinci_sdk::rmp_serialize(&data).unwrap();
+
+        assert_eq!(hex::encode(&buf), SEALED_DATA_HEX);
+    }
+
+    #[test]
+    fn sealed_data_deserialize() {
+        let expected = create_sealed_data();
+        let buf = hex::decode(SEALED_DATA_HEX).unwrap();
+
+        let data: SealedData = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(data, expected);
+    }
+
+    #[test]
+    fn store_envelope_args_serialize() {
+        let args = StoreEnvelopeArgs {
+            key: "data",
+            envelope: create_envelope(),
+            force: false,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), STORE_ENVELOPE_ARGS_HEX);
+    }
+
+    #[test]
+    fn store_envelope_args_deserialize() {
+        let expected = StoreEnvelopeArgs {
+            key: "data",
+            envelope: create_envelope(),
+            force: false,
+        };
+        let buf = hex::decode(STORE_ENVELOPE_ARGS_HEX).unwrap();
+
+        let args: StoreEnvelopeArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn add_recipient_args_serialize() {
+        let args = AddRecipientArgs {
+            key: "data",
+            recipient: "Dave",
+            wrapped_key: &[0xab, 0xcd],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), ADD_RECIPIENT_ARGS_HEX);
+    }
+
+    #[test]
+    fn add_recipient_args_deserialize() {
+        let expected = AddRecipientArgs {
+            key: "data",
+            recipient: "Dave",
+            wrapped_key: &[0xab, 0xcd],
+        };
+        let buf = hex::decode(ADD_RECIPIENT_ARGS_HEX).unwrap();
+
+        let args: AddRecipientArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn revoke_recipient_args_serialize() {
+        let args = RevokeRecipientArgs {
+            key: "data",
+            recipient: "Bob",
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), REVOKE_RECIPIENT_ARGS_HEX);
+    }
+
+    #[test]
+    fn revoke_recipient_args_deserialize() {
+        let expected = RevokeRecipientArgs {
+            key: "data",
+            recipient: "Bob",
+        };
+        let buf = hex::decode(REVOKE_RECIPIENT_ARGS_HEX).unwrap();
+
+        let args: RevokeRecipientArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn set_field_args_serialize() {
+        let args = SetFieldArgs {
+            key: "doc",
+            path: vec!["name"],
+            value: Value::String("Bob".to_string()),
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), SET_FIELD_ARGS_HEX);
+    }
+
+    #[test]
+    fn set_field_args_deserialize() {
+        let expected = SetFieldArgs {
+            key: "doc",
+            path: vec!["name"],
+            value: Value::String("Bob".to_string()),
+        };
+        let buf = hex::decode(SET_FIELD_ARGS_HEX).unwrap();
+
+        let args: SetFieldArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn quota_serialize() {
+        let quota = Quota {
+            max_keys: 10,
+            max_value_size: 256,
+            max_total_size: 1024,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&quota).unwrap();
+
+        assert_eq!(hex::encode(&buf), QUOTA_HEX);
+    }
+
+    #[test]
+    fn quota_deserialize() {
+        let expected = Quota {
+            max_keys: 10,
+            max_value_size: 256,
+            max_total_size: 1024,
+        };
+        let buf = hex::decode(QUOTA_HEX).unwrap();
+
+        let quota: Quota = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(quota, expected);
+    }
+
+    #[test]
+    fn set_protected_args_serialize() {
+        let args = SetProtectedArgs {
+            patterns: vec!["config", "asset:*"],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), SET_PROTECTED_ARGS_HEX);
+    }
+
+    #[test]
+    fn set_protected_args_deserialize() {
+        let expected = SetProtectedArgs {
+            patterns: vec!["config", "asset:*"],
+        };
+        let buf = hex::decode(SET_PROTECTED_ARGS_HEX).unwrap();
+
+        let args: SetProtectedArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn notarize_args_serialize() {
+        let args = NotarizeArgs {
+            hash: &[0xab, 0xcd],
+            metadata: Some("doc"),
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), NOTARIZE_ARGS_HEX);
+    }
+
+    #[test]
+    fn notarize_args_deserialize() {
+        let expected = NotarizeArgs {
+            hash: &[0xab, 0xcd],
+            metadata: Some("doc"),
+        };
+        let buf = hex::decode(NOTARIZE_ARGS_HEX).unwrap();
+
+        let args: NotarizeArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    #[test]
+    fn notary_record_serialize() {
+        let record = NotaryRecord {
+            submitter: "Bob",
+            timestamp: 1000,
+            metadata: None,
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&record).unwrap();
+
+        assert_eq!(hex::encode(&buf), NOTARY_RECORD_HEX);
+    }
+
+    #[test]
+    fn notary_record_deserialize() {
+        let expected = NotaryRecord {
+            submitter: "Bob",
+            timestamp: 1000,
+            metadata: None,
+        };
+        let buf = hex::decode(NOTARY_RECORD_HEX).unwrap();
+
+        let record: NotaryRecord = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(record, expected);
+    }
+
+    #[test]
+    fn balances_args_serialize() {
+        let args = BalancesArgs {
+            assets: vec!["FCK", "EUR"],
+        };
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), BALANCES_ARGS_HEX);
+    }
+
+    #[test]
+    fn balances_args_deserialize() {
+        let expected = BalancesArgs {
+            assets: vec!["FCK", "EUR"],
+        };
+        let buf = hex::decode(BALANCES_ARGS_HEX).unwrap();
+
+        let args: BalancesArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_multi_transfer_args() -> MultiTransferArgs<'static> {
+        MultiTransferArgs {
+            transfers: vec![
+                TransferArgs {
+                    to: "Bob",
+                    asset: "FCK",
+                    units: 3,
+                },
+                TransferArgs {
+                    to: "Dave",
+                    asset: "EUR",
+                    units: 5,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn multi_transfer_args_serialize() {
+        let args = create_multi_transfer_args();
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), MULTI_TRANSFER_ARGS_HEX);
+    }
+
+    #[test]
+    fn multi_transfer_args_deserialize() {
+        let expected = create_multi_transfer_args();
+        let buf = hex::decode(MULTI_TRANSFER_ARGS_HEX).unwrap();
+
+        let args: MultiTransferArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_add_order_args() -> AddOrderArgs<'static> {
+        AddOrderArgs {
+            id: "rent",
+            to: "Bob",
+            asset: "FCK",
+            units: 50,
+            reward: 1,
+            start: 1000,
+            interval: 3600,
+        }
+    }
+
+    #[test]
+    fn add_order_args_serialize() {
+        let args = create_add_order_args();
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), ADD_ORDER_ARGS_HEX);
+    }
+
+    #[test]
+    fn add_order_args_deserialize() {
+        let expected = create_add_order_args();
+        let buf = hex::decode(ADD_ORDER_ARGS_HEX).unwrap();
+
+        let args: AddOrderArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_order() -> Order<'static> {
+        Order {
+            to: "Bob",
+            asset: "FCK",
+            units: 50,
+            reward: 1,
+            interval: 3600,
+            next: 4600,
+            executions: 1,
+            active: true,
+        }
+    }
+
+    #[test]
+    fn order_serialize() {
+        let order = create_order();
+
+        let buf = trinci_sdk::rmp_serialize(&order).unwrap();
+
+        assert_eq!(hex::encode(&buf), ORDER_HEX);
+    }
+
+    #[test]
+    fn order_deserialize() {
+        let expected = create_order();
+        let buf = hex::decode(ORDER_HEX).unwrap();
+
+        let order: Order = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(order, expected);
+    }
+
+    fn create_set_operator_args() -> SetOperatorArgs<'static> {
+        let mut limits = BTreeMap::new();
+        limits.insert(
+            "FCK",
+            SpendingLimit {
+                units: 100,
+                period: 86400,
+            },
+        );
+        SetOperatorArgs {
+            account: "Bob",
+            limits,
+            destinations: vec!["Dave"],
+        }
+    }
+
+    #[test]
+    fn set_operator_args_serialize() {
+        let args = create_set_operator_args();
+
+        let buf = trinci_sdk::rmp_serialize(&args).unwrap();
+
+        assert_eq!(hex::encode(&buf), SET_OPERATOR_ARGS_HEX);
+    }
+
+    #[test]
+    fn set_operator_args_deserialize() {
+        let expected = create_set_operator_args();
+        let buf = hex::decode(SET_OPERATOR_ARGS_HEX).unwrap();
+
+        let args: SetOperatorArgs = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(args, expected);
+    }
+
+    fn create_spend_record() -> SpendRecord<'static> {
+        SpendRecord {
+            operator: "Bob",
+            to: "Dave",
+            asset: "FCK",
+            units: 10,
+            timestamp: 1000,
+        }
+    }
+
+    #[test]
+    fn spend_record_serialize() {
+        let record = create_spend_record();
+
+        let buf = trinci_sdk::rmp_serialize(&record).unwrap();
+
+        assert_eq!(hex::encode(&buf), SPEND_RECORD_HEX);
+    }
+
+    #[test]
+    fn spend_record_deserialize() {
+        let expected = create_spend_record();
+        let buf = hex::decode(SPEND_RECORD_HEX).unwrap();
+
+        let record: SpendRecord = trinci_sdk::rmp_deserialize(&buf).unwrap();
+
+        assert_eq!(record, expected);
+    }
 }
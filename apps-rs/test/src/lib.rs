@@ -25,16 +25,19 @@
 //!
 use random::Source;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::time::SystemTime;
+use serde_bytes::ByteBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::hint::black_box;
+use std::time::{Instant, SystemTime};
 use std::{
     alloc::{alloc, Layout},
     convert::TryInto,
     mem::align_of,
 };
 use trinci_sdk::{
-    rmp_deserialize, rmp_serialize, tai::AssetTransferArgs, value, AppContext, PackedValue, Value,
-    WasmError, WasmResult,
+    rmp_deserialize, rmp_serialize,
+    tai::{AssetTransferArgs, LockType},
+    value, AppContext, PackedValue, PublicKey, Value, WasmError, WasmResult,
 };
 trinci_sdk::app_export!(
     init,
@@ -42,8 +45,17 @@ trinci_sdk::app_export!(
     echo_generic,
     echo_typed,
     echo_packed,
+    echo_numbers,
+    echo_options,
+    echo_bytes,
+    echo_maps,
+    echo_ext,
     // Default smart contract functionalities.
     nested_call,
+    call_chain,
+    store_and_call,
+    store_and_catch,
+    nested_store,
     balance,
     transfer,
     notify,
@@ -57,6 +69,16 @@ trinci_sdk::app_export!(
     secure_call_test,
     test_hf_drand,
     test_hf_is_callable,
+    test_hf_load_data,
+    test_hf_remove_data,
+    test_hf_load_asset,
+    test_hf_store_asset,
+    test_hf_verify,
+    test_hf_get_block_time,
+    test_hf_asset_balance,
+    test_hf_asset_transfer,
+    test_hf_asset_lock,
+    test_context,
     // Trigger exceptional conditions.
     divide_by_zero,
     trigger_panic,
@@ -64,12 +86,37 @@ trinci_sdk::app_export!(
     infinite_recursion,
     infinite_loop,
     null_pointer_indirection,
+    alloc_pages,
+    recurse_to,
+    emit_events,
+    store_bytes,
     // Deterministic contract
     get_random_sequence,
     get_hashmap,
-    get_time
+    get_time,
+    get_nan_bits,
+    get_env,
+    spawn_thread,
+    get_instant,
+    get_alloc_offsets,
+    get_simd,
+    // Fuel consumption benchmarks
+    fuel_loop,
+    fuel_hash,
+    fuel_store,
+    fuel_nested
 );
 
+/// Size of a wasm memory page.
+#[cfg(not(target_arch = "wasm32"))]
+const WASM_PAGE_SIZE: usize = 64 * 1024;
+
+/// Key written by `store_bytes`.
+const PROBE_KEY: &str = "probe";
+
+/// Prefix of the keys written by `fuel_store`.
+const FUEL_KEY_PREFIX: &str = "fuel:";
+
 #[derive(Serialize, Deserialize, Debug)]
 #[cfg_attr(test, derive(PartialEq, Clone, Default))]
 struct SubStruct<'a> {
@@ -108,6 +155,97 @@ struct SecureCallArgs {
     pub data: Vec<u8>,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct StoreAssetArgs<'a> {
+    pub account: &'a str,
+    pub units: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone))]
+struct VerifyArgs {
+    pub pk: PublicKey,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub sign: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct AssetTransferTestArgs<'a> {
+    pub asset: &'a str,
+    pub to: &'a str,
+    pub units: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct AssetLockTestArgs<'a> {
+    pub asset: &'a str,
+    pub to: &'a str,
+    pub lock: LockType,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct ContextInfo<'a> {
+    pub depth: u16,
+    pub network: &'a str,
+    pub owner: &'a str,
+    pub caller: &'a str,
+    pub method: &'a str,
+    pub origin: &'a str,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct NestedStoreArgs<'a> {
+    pub key: &'a str,
+    pub fail: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct NumbersArgs {
+    pub max_u64: u64,
+    pub min_i64: i64,
+    pub negative: i8,
+    pub float32: f32,
+    pub float64: f64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct OptionsArgs {
+    pub value: Option<u32>,
+    #[serde(default)]
+    pub missing: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct BytesArgs {
+    #[serde(with = "serde_bytes")]
+    pub bin: Vec<u8>,
+    pub array: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+struct MapsArgs<'a> {
+    #[serde(borrow)]
+    pub by_int: BTreeMap<u32, BTreeMap<i64, &'a str>>,
+    pub by_bool: BTreeMap<bool, Vec<u8>>,
+}
+
+/// Msgpack extension type, `rmp_serde` maps it on the `_ExtStruct` newtype.
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(test, derive(PartialEq, Clone, Default))]
+#[serde(rename = "_ExtStruct")]
+struct ExtValue((i8, ByteBuf));
+
 /// Only binds the contract to an account
 fn init(_ctx: AppContext, _args: PackedValue) -> WasmResult<()> {
     Ok(())
@@ -131,6 +269,41 @@ fn echo_packed(ctx: AppContext, args: PackedValue) -> WasmResult<PackedValue> {
     Ok(args)
 }
 
+/// Returns the input data "as is".
+///
+/// Integers at the limits of their range and floating point values.
+fn echo_numbers(_ctx: AppContext, args: NumbersArgs) -> WasmResult<NumbersArgs> {
+    Ok(args)
+}
+
+/// Returns the input data "as is".
+///
+/// Both a `nil` and a missing trailing field are decoded as `None`.
+fn echo_options(_ctx: AppContext, args: OptionsArgs) -> WasmResult<OptionsArgs> {
+    Ok(args)
+}
+
+/// Returns the input data "as is".
+///
+/// The `bin` and the `array` encodings of the bytes are preserved.
+fn echo_bytes(_ctx: AppContext, args: BytesArgs) -> WasmResult<BytesArgs> {
+    Ok(args)
+}
+
+/// Returns the input data "as is".
+///
+/// Nested maps with non-string keys.
+fn echo_maps<'a>(_ctx: AppContext, args: MapsArgs<'a>) -> WasmResult<MapsArgs<'a>> {
+    Ok(args)
+}
+
+/// Returns the input data "as is".
+///
+/// Msgpack extension type.
+fn echo_ext(_ctx: AppContext, args: ExtValue) -> WasmResult<ExtValue> {
+    Ok(args)
+}
+
 /// Trampoline method to call another one via `sdk::call`.
 fn nested_call(ctx: AppContext, args: Value) -> WasmResult<Value> {
     let input = rmp_serialize(&args).unwrap();
@@ -138,6 +311,78 @@ fn nested_call(ctx: AppContext, args: Value) -> WasmResult<Value> {
     rmp_deserialize(&output)
 }
 
+#[inline]
+fn context_info<'a>(ctx: &AppContext<'a>) -> ContextInfo<'a> {
+    ContextInfo {
+        depth: ctx.depth,
+        network: ctx.network,
+        owner: ctx.owner,
+        caller: ctx.caller,
+        method: ctx.method,
+        origin: ctx.origin,
+    }
+}
+
+/// Call `call_chain` on the first account of the list, passing the rest.
+///
+/// Returns the context of every level of the chain: a list repeating the
+/// account tests the call depth, a list ending with the caller tests the
+/// reentrancy.
+fn call_chain(ctx: AppContext, accounts: Vec<&str>) -> WasmResult<PackedValue> {
+    let mut contexts = vec![context_info(&ctx)];
+
+    let output;
+    if let Some((next, rest)) = accounts.split_first() {
+        let input = rmp_serialize(&rest)?;
+        output = trinci_sdk::call(next, "call_chain", &input)?;
+        let nested: Vec<ContextInfo> = rmp_deserialize(&output)?;
+        contexts.extend(nested);
+    }
+
+    let buf = rmp_serialize(&contexts)?;
+    Ok(PackedValue(buf))
+}
+
+/// Store the `key` then call `nested_store` on the account itself, the
+/// nested call stores `key:nested` and then fails if requested.
+fn store_then_nested(ctx: &AppContext, args: &NestedStoreArgs) -> WasmResult<Vec<u8>> {
+    trinci_sdk::store_data(args.key, &[1]);
+
+    let key = format!("{}:nested", args.key);
+    let nested = NestedStoreArgs {
+        key: &key,
+        fail: args.fail,
+    };
+    let input = rmp_serialize(&nested)?;
+    trinci_sdk::call(ctx.owner, "nested_store", &input)
+}
+
+/// Store the `key` then call `nested_store` on the account itself.
+///
+/// The nested failure is propagated so that both the keys shall be rolled
+/// back.
+fn store_and_call(ctx: AppContext, args: NestedStoreArgs) -> WasmResult<()> {
+    store_then_nested(&ctx, &args)?;
+    Ok(())
+}
+
+/// Store the `key` then call `nested_store` on the account itself.
+///
+/// The nested failure is caught, so that only the nested key shall be
+/// rolled back. Returns `true` if the nested call failed.
+fn store_and_catch(ctx: AppContext, args: NestedStoreArgs) -> WasmResult<bool> {
+    Ok(store_then_nested(&ctx, &args).is_err())
+}
+
+/// Store the `key` and then fail if requested.
+fn nested_store(_ctx: AppContext, args: NestedStoreArgs) -> WasmResult<()> {
+    trinci_sdk::store_data(args.key, &[1]);
+    if args.fail {
+        return Err(WasmError::new("nested call failure"));
+    }
+    Ok(())
+}
+
 #[inline]
 fn load_my_asset(account: &str) -> u64 {
     let buf = trinci_sdk::load_asset(account);
@@ -198,6 +443,64 @@ fn test_hf_is_callable(ctx: AppContext, method: &str) -> WasmResult<bool> {
     Ok(trinci_sdk::is_callable(ctx.owner, method))
 }
 
+/// Call the host function hf_load_data
+fn test_hf_load_data(_ctx: AppContext, key: &str) -> WasmResult<Vec<u8>> {
+    Ok(trinci_sdk::load_data(key))
+}
+
+/// Call the host function hf_remove_data
+fn test_hf_remove_data(_ctx: AppContext, key: &str) -> WasmResult<()> {
+    trinci_sdk::remove_data(key);
+    Ok(())
+}
+
+/// Call the host function hf_load_asset
+///
+/// Returns the units of the contract account asset held by `account`.
+fn test_hf_load_asset(_ctx: AppContext, account: &str) -> WasmResult<u64> {
+    Ok(load_my_asset(account))
+}
+
+/// Call the host function hf_store_asset
+///
+/// Set the units of the contract account asset held by `account`.
+fn test_hf_store_asset(_ctx: AppContext, args: StoreAssetArgs) -> WasmResult<()> {
+    store_my_asset(args.account, args.units);
+    Ok(())
+}
+
+/// Call the host function hf_verify
+fn test_hf_verify(_ctx: AppContext, args: VerifyArgs) -> WasmResult<bool> {
+    Ok(trinci_sdk::verify(&args.pk, &args.data, &args.sign))
+}
+
+/// Call the host function hf_get_block_time
+fn test_hf_get_block_time(_ctx: AppContext, _args: PackedValue) -> WasmResult<u64> {
+    Ok(trinci_sdk::get_block_time())
+}
+
+/// Call the `balance` method of an asset via hf_call
+fn test_hf_asset_balance(_ctx: AppContext, asset: &str) -> WasmResult<u64> {
+    trinci_sdk::asset_balance(asset)
+}
+
+/// Call the `transfer` method of an asset via hf_call
+///
+/// Transfer the units from the contract account.
+fn test_hf_asset_transfer(ctx: AppContext, args: AssetTransferTestArgs) -> WasmResult<()> {
+    trinci_sdk::asset_transfer(ctx.owner, args.to, args.asset, args.units)
+}
+
+/// Call the `lock` method of an asset via hf_call
+fn test_hf_asset_lock(_ctx: AppContext, args: AssetLockTestArgs) -> WasmResult<()> {
+    trinci_sdk::asset_lock(args.asset, args.to, args.lock)
+}
+
+/// Returns the context the method has been called with.
+fn test_context(ctx: AppContext, _args: PackedValue) -> WasmResult<ContextInfo> {
+    Ok(context_info(&ctx))
+}
+
 /// Call the host function hf_transfer
 ///
 /// Transfer an *amount* of *asset* from the *caller account* to the *dest account*
@@ -305,6 +608,73 @@ fn null_pointer_indirection(_ctx: AppContext, _args: Value) -> WasmResult<Value>
     Ok(value!(null))
 }
 
+/// Grow the memory by exactly `pages` wasm pages
+///
+/// Returns the memory size in pages after the growth.
+fn alloc_pages(_ctx: AppContext, pages: u32) -> WasmResult<u64> {
+    trinci_sdk::log("Called method `alloc_pages`");
+
+    match memory_grow(pages as usize) {
+        Some(size) => Ok(size as u64),
+        None => Err(WasmError::new("memory grow failure")),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn memory_grow(pages: usize) -> Option<usize> {
+    match core::arch::wasm32::memory_grow::<0>(pages) {
+        usize::MAX => None,
+        prev => Some(prev + pages),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn memory_grow(pages: usize) -> Option<usize> {
+    // Outside the wasm environment the growth is emulated by a reservation.
+    let mut buf = Vec::<u8>::new();
+    buf.try_reserve_exact(pages.checked_mul(WASM_PAGE_SIZE)?)
+        .ok()?;
+    Some(pages)
+}
+
+/// Recurse to exactly `depth` levels
+///
+/// Returns the depth reached.
+fn recurse_to(_ctx: AppContext, depth: u64) -> WasmResult<u64> {
+    trinci_sdk::log("Called method `recurse_to`");
+
+    Ok(recurse(depth))
+}
+
+#[inline(never)]
+fn recurse(depth: u64) -> u64 {
+    // Keep a fixed size frame on the stack at every level.
+    let frame = black_box([depth; 8]);
+    if frame[0] == 0 {
+        0
+    } else {
+        1 + recurse(frame[7] - 1)
+    }
+}
+
+/// Emit exactly `n` events
+fn emit_events(_ctx: AppContext, n: u64) -> WasmResult<()> {
+    trinci_sdk::log("Called method `emit_events`");
+
+    for i in 0..n {
+        trinci_sdk::emit_data("probe", &i.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Store exactly `n` bytes of data in the account
+fn store_bytes(_ctx: AppContext, n: u64) -> WasmResult<()> {
+    trinci_sdk::log("Called method `store_bytes`");
+
+    trinci_sdk::store_data(PROBE_KEY, &vec![0xAB; n as usize]);
+    Ok(())
+}
+
 /// Send a notification to the host.
 fn notify(ctx: AppContext, data: Value) -> WasmResult<()> {
     trinci_sdk::emit_data_mp!("event_a", &data)?;
@@ -347,11 +717,152 @@ fn get_time(_ctx: AppContext, _args: PackedValue) -> WasmResult<u64> {
     Ok(sys_time.as_secs())
 }
 
+/// Return the bits of NaN values produced by the float operations
+/// (shall be canonicalized).
+fn get_nan_bits(_ctx: AppContext, _args: PackedValue) -> WasmResult<Vec<u64>> {
+    trinci_sdk::log("Called method `get_nan_bits`");
+
+    let zero = black_box(0.0f64);
+    let other_zero = black_box(0.0f64);
+    let nan32 = black_box(f32::NAN);
+    let bits = vec![
+        (zero / other_zero).to_bits(),
+        (-(zero / other_zero)).to_bits(),
+        (black_box(-1.0f64) * f64::NAN).to_bits(),
+        black_box(-1.0f64).sqrt().to_bits(),
+        (f64::INFINITY - black_box(f64::INFINITY)).to_bits(),
+        f64::from(-nan32).to_bits(),
+        u64::from((nan32 + 1.0).to_bits()),
+    ];
+    Ok(bits)
+}
+
+/// Try to access to the environment variables and arguments.
+fn get_env(_ctx: AppContext, _args: PackedValue) -> WasmResult<Vec<String>> {
+    trinci_sdk::log("Called method `get_env`");
+
+    let mut env: Vec<String> = std::env::vars()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    env.extend(std::env::args());
+    if let Ok(dir) = std::env::current_dir() {
+        env.push(dir.display().to_string());
+    }
+    Ok(env)
+}
+
+/// Try to spawn a thread.
+fn spawn_thread(_ctx: AppContext, _args: PackedValue) -> WasmResult<String> {
+    trinci_sdk::log("Called method `spawn_thread`");
+
+    let res = match std::thread::Builder::new().spawn(|| 42u64) {
+        Ok(handle) => match handle.join() {
+            Ok(val) => format!("thread returned {}", val),
+            Err(_) => "thread panicked".to_string(),
+        },
+        Err(err) => err.to_string(),
+    };
+    Ok(res)
+}
+
+/// Try to access to the monotonic clock.
+fn get_instant(_ctx: AppContext, _args: PackedValue) -> WasmResult<u64> {
+    trinci_sdk::log("Called method `get_instant`");
+
+    let start = Instant::now();
+    black_box((0..1000u64).sum::<u64>());
+    Ok(start.elapsed().as_nanos() as u64)
+}
+
+/// Return the addresses of some allocations relative to the first one
+/// (shall be deterministic)
+fn get_alloc_offsets(_ctx: AppContext, _args: PackedValue) -> WasmResult<Vec<i64>> {
+    trinci_sdk::log("Called method `get_alloc_offsets`");
+
+    let boxes: Vec<Box<[u8]>> = [64, 8, 4096, 1, 256]
+        .iter()
+        .map(|size| vec![0u8; *size].into_boxed_slice())
+        .collect();
+    let base = boxes[0].as_ptr() as i64;
+    let offsets = boxes.iter().map(|b| b.as_ptr() as i64 - base).collect();
+    Ok(offsets)
+}
+
+/// Return the results of some SIMD float operations, `None` if the contract
+/// has not been compiled with the `simd128` target feature.
+fn get_simd(_ctx: AppContext, _args: PackedValue) -> WasmResult<Option<Vec<u8>>> {
+    trinci_sdk::log("Called method `get_simd`");
+
+    Ok(simd_probe())
+}
+
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+fn simd_probe() -> Option<Vec<u8>> {
+    use core::arch::wasm32::{f32x4, f32x4_add, f32x4_min, f32x4_neg, f32x4_sqrt, v128};
+
+    let a = f32x4(1.5, -0.0, black_box(f32::NAN), f32::INFINITY);
+    let b = f32x4(black_box(0.0), 0.0, 1.0, f32::NEG_INFINITY);
+    let results = [f32x4_add(a, b), f32x4_min(a, b), f32x4_sqrt(f32x4_neg(a))];
+
+    let mut buf = Vec::with_capacity(16 * results.len());
+    for val in results {
+        // Safety: `v128` and `[u8; 16]` have the same size.
+        let bytes = unsafe { std::mem::transmute::<v128, [u8; 16]>(val) };
+        buf.extend_from_slice(&bytes);
+    }
+    Some(buf)
+}
+
+#[cfg(not(all(target_arch = "wasm32", target_feature = "simd128")))]
+fn simd_probe() -> Option<Vec<u8>> {
+    None
+}
+
+/// Fuel benchmark: loop with `n` iterations
+fn fuel_loop(_ctx: AppContext, n: u64) -> WasmResult<u64> {
+    let mut acc = 0u64;
+    for i in 0..n {
+        acc = black_box(acc.wrapping_mul(31).wrapping_add(i));
+    }
+    Ok(acc)
+}
+
+/// Fuel benchmark: sha256 of `n` bytes
+fn fuel_hash(_ctx: AppContext, n: u64) -> WasmResult<Vec<u8>> {
+    let data: Vec<u8> = (0..n).map(|i| i as u8).collect();
+    Ok(trinci_sdk::sha256(&data))
+}
+
+/// Fuel benchmark: store `n` keys in the account
+fn fuel_store(_ctx: AppContext, n: u64) -> WasmResult<()> {
+    for i in 0..n {
+        let key = format!("{}{}", FUEL_KEY_PREFIX, i);
+        trinci_sdk::store_data(&key, &i.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Fuel benchmark: chain of `n` nested calls to the account itself
+///
+/// Returns the number of nested calls.
+fn fuel_nested(ctx: AppContext, n: u64) -> WasmResult<u64> {
+    if n == 0 {
+        return Ok(0);
+    }
+    let args = rmp_serialize(&(n - 1))?;
+    let buf = trinci_sdk::call(ctx.owner, "fuel_nested", &args)?;
+    let depth: u64 = rmp_deserialize(&buf)?;
+    Ok(depth + 1)
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
-    use trinci_sdk::not_wasm;
+    use trinci_sdk::{
+        not_wasm,
+        tai::{Asset, LockPrivilege},
+    };
 
     const OWNER_ID: &str = "QmYHnEQLdf5h7KYbjFPuHSRk2SPgdXrJWFh5W696HPfq7i";
     const CALLER_ID: &str = OWNER_ID;
@@ -434,6 +945,145 @@ mod tests {
         assert_eq!(input, output);
     }
 
+    const NUMBERS_HEX: &str =
+        "95cfffffffffffffffffd38000000000000000ffca3fc00000cbbfb999999999999a";
+    const MAPS_HEX: &str =
+        "92820080ceffffffff82ffa96d696e7573206f6e65cf7fffffffffffffffa36d617882c290c393010203";
+    const EXT_HEX: &str = "d62a01020304";
+
+    /// Decode the buffer, call the echo method and encode its result.
+    fn echo_wire<'a, T, R>(f: fn(AppContext<'a>, T) -> WasmResult<R>, buf: &'a [u8]) -> Vec<u8>
+    where
+        T: Deserialize<'a>,
+        R: Serialize,
+    {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        let args: T = rmp_deserialize(buf).unwrap();
+        let output = f(ctx, args).unwrap();
+        rmp_serialize(&output).unwrap()
+    }
+
+    #[test]
+    fn echo_numbers_limits() {
+        let input = NumbersArgs {
+            max_u64: u64::MAX,
+            min_i64: i64::MIN,
+            negative: -1,
+            float32: 1.5,
+            float64: -0.1,
+        };
+        let buf = rmp_serialize(&input).unwrap();
+        assert_eq!(hex::encode(&buf), NUMBERS_HEX);
+
+        let output = echo_wire(echo_numbers, &buf);
+
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn echo_options_nil_and_missing() {
+        // Explicit nil, missing trailing field and struct as map.
+        for input in ["92c0c0", "91c0", "81a576616c7565c0"] {
+            let buf = hex::decode(input).unwrap();
+
+            let output = echo_wire(echo_options, &buf);
+
+            assert_eq!(hex::encode(output), "92c0c0");
+        }
+    }
+
+    #[test]
+    fn echo_options_missing_required() {
+        let buf = hex::decode("90").unwrap();
+
+        let err = rmp_deserialize::<OptionsArgs>(&buf).unwrap_err();
+
+        assert_eq!(err.to_string(), "deserialization failure");
+    }
+
+    #[test]
+    fn echo_empty_bin_and_array() {
+        let input = BytesArgs::default();
+        let buf = rmp_serialize(&input).unwrap();
+        assert_eq!(hex::encode(&buf), "92c40090");
+
+        let output = echo_wire(echo_bytes, &buf);
+
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn echo_large_payload() {
+        let input = BytesArgs {
+            bin: vec![0xAA; 100_000],
+            array: vec![0x55; 100_000],
+        };
+        let buf = rmp_serialize(&input).unwrap();
+        // bin 32 marker
+        assert_eq!(buf[1], 0xc6);
+
+        let output = echo_wire(echo_bytes, &buf);
+
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn echo_maps_non_string_keys() {
+        let mut inner = BTreeMap::new();
+        inner.insert(-1, "minus one");
+        inner.insert(i64::MAX, "max");
+        let mut by_int = BTreeMap::new();
+        by_int.insert(0, BTreeMap::new());
+        by_int.insert(u32::MAX, inner);
+        let mut by_bool = BTreeMap::new();
+        by_bool.insert(false, vec![]);
+        by_bool.insert(true, vec![1, 2, 3]);
+        let input = MapsArgs { by_int, by_bool };
+        let buf = rmp_serialize(&input).unwrap();
+        assert_eq!(hex::encode(&buf), MAPS_HEX);
+
+        let output = echo_wire(echo_maps, &buf);
+
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn echo_ext_type() {
+        let input = ExtValue((42, ByteBuf::from(vec![0x01, 0x02, 0x03, 0x04])));
+        let buf = rmp_serialize(&input).unwrap();
+        assert_eq!(hex::encode(&buf), EXT_HEX);
+
+        let output = echo_wire(echo_ext, &buf);
+
+        assert_eq!(output, buf);
+    }
+
+    #[test]
+    fn echo_malformed_input() {
+        let malformed = [
+            // Empty buffer
+            "",
+            // Truncated array
+            "95cfffffffffffffffff",
+            // Reserved marker
+            "c1",
+            // String in place of an integer
+            "95a3666f6fd38000000000000000ffca3fc00000cbbfb999999999999a",
+            // Negative integer in place of an unsigned one
+            "95ffd38000000000000000ffca3fc00000cbbfb999999999999a",
+            // Out of range integer
+            "95cfffffffffffffffffd38000000000000000cc80ca3fc00000cbbfb999999999999a",
+        ];
+
+        for input in malformed {
+            let buf = hex::decode(input).unwrap();
+
+            let err = rmp_deserialize::<NumbersArgs>(&buf).unwrap_err();
+
+            assert_eq!(err.to_string(), "deserialization failure", "{}", input);
+        }
+    }
+
     #[test]
     fn echo_nested_call() {
         let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
@@ -718,6 +1368,195 @@ mod tests {
         assert_eq!(err.to_string(), "incompatible contract app");
     }
 
+    #[test]
+    fn load_and_remove_data() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_account_data(OWNER_ID, "foo", &[1, 2, 3]);
+
+        let res = not_wasm::call_wrap(test_hf_load_data, ctx, "foo").unwrap();
+        assert_eq!(res, [1, 2, 3]);
+
+        not_wasm::call_wrap(test_hf_remove_data, ctx, "foo").unwrap();
+
+        let res = not_wasm::call_wrap(test_hf_load_data, ctx, "foo").unwrap();
+        assert_eq!(res, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn store_and_load_foreign_asset() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        let args = StoreAssetArgs {
+            account: "abcdef",
+            units: 42,
+        };
+
+        not_wasm::call_wrap(test_hf_store_asset, ctx, args).unwrap();
+
+        let buf = not_wasm::get_account_asset("abcdef", OWNER_ID);
+        assert_eq!(buf, 42_u64.to_be_bytes());
+        let res = not_wasm::call_wrap(test_hf_load_asset, ctx, "abcdef").unwrap();
+        assert_eq!(res, 42);
+    }
+
+    #[test]
+    fn load_missing_asset() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let res = not_wasm::call_wrap(test_hf_load_asset, ctx, "abcdef").unwrap();
+
+        assert_eq!(res, 0);
+    }
+
+    #[test]
+    fn get_block_time() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_block_time(1234);
+
+        let res = not_wasm::call_wrap(test_hf_get_block_time, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(res, 1234);
+    }
+
+    #[test]
+    fn asset_lock_call() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_contract_method("asset", "lock", not_wasm::asset_lock);
+        let args = AssetLockTestArgs {
+            asset: "asset",
+            to: OWNER_ID,
+            lock: LockType::Full,
+        };
+
+        not_wasm::call_wrap(test_hf_asset_lock, ctx, args).unwrap();
+
+        let asset: Asset = not_wasm::get_account_asset_gen(OWNER_ID, "asset");
+        assert_eq!(asset.lock, Some((LockPrivilege::Contract, LockType::Full)));
+    }
+
+    #[test]
+    fn asset_transfer_call() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_contract_method("asset", "transfer", not_wasm::asset_transfer);
+        not_wasm::set_contract_method("asset", "balance", not_wasm::asset_balance);
+        not_wasm::set_account_asset_gen(OWNER_ID, "asset", Asset::new(9));
+        let args = AssetTransferTestArgs {
+            asset: "asset",
+            to: "abcdef",
+            units: 3,
+        };
+
+        not_wasm::call_wrap(test_hf_asset_transfer, ctx, args).unwrap();
+
+        let res = not_wasm::call_wrap(test_hf_asset_balance, ctx, "asset").unwrap();
+        assert_eq!(res, 6);
+        let asset: Asset = not_wasm::get_account_asset_gen("abcdef", "asset");
+        assert_eq!(asset.units, 3);
+    }
+
+    #[test]
+    fn context_fields() {
+        let mut ctx = not_wasm::create_app_context(OWNER_ID, "caller");
+        ctx.depth = 1;
+        ctx.origin = "origin";
+
+        let res = not_wasm::call_wrap(test_context, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(res.depth, 1);
+        assert_eq!(res.owner, OWNER_ID);
+        assert_eq!(res.caller, "caller");
+        assert_eq!(res.origin, "origin");
+    }
+
+    #[test]
+    fn fuel_loop_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let res = not_wasm::call_wrap(fuel_loop, ctx, 3).unwrap();
+
+        assert_eq!(res, 33);
+    }
+
+    #[test]
+    fn fuel_store_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        not_wasm::call_wrap(fuel_store, ctx, 3).unwrap();
+
+        let buf = not_wasm::get_account_data(OWNER_ID, "fuel:2");
+        assert_eq!(buf, 2_u64.to_be_bytes());
+        let buf = not_wasm::get_account_data(OWNER_ID, "fuel:3");
+        assert_eq!(buf, Vec::<u8>::new());
+    }
+
+    #[test]
+    fn fuel_nested_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        // The nested call echoes back the depth it has been called with
+        not_wasm::set_contract_method(OWNER_ID, "fuel_nested", echo_packed);
+
+        let res = not_wasm::call_wrap(fuel_nested, ctx, 5).unwrap();
+
+        assert_eq!(res, 5);
+    }
+
+    #[test]
+    fn call_chain_end() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, "caller");
+
+        let res = not_wasm::call_wrap(call_chain, ctx, Vec::<&str>::new()).unwrap();
+
+        let contexts: Vec<ContextInfo> = rmp_deserialize(&res.0).unwrap();
+        assert_eq!(contexts.len(), 1);
+        assert_eq!(contexts[0].depth, 0);
+        assert_eq!(contexts[0].caller, "caller");
+    }
+
+    #[test]
+    fn nested_store_failure() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        let args = NestedStoreArgs {
+            key: "foo",
+            fail: true,
+        };
+
+        let err = not_wasm::call_wrap(nested_store, ctx, args).unwrap_err();
+
+        assert_eq!(err.to_string(), "nested call failure");
+    }
+
+    #[test]
+    fn store_and_call_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_contract_method(OWNER_ID, "nested_store", echo_packed);
+        let args = NestedStoreArgs {
+            key: "foo",
+            fail: false,
+        };
+
+        not_wasm::call_wrap(store_and_call, ctx, args).unwrap();
+
+        assert_eq!(not_wasm::get_account_data(OWNER_ID, "foo"), [1]);
+    }
+
+    fn fail_packed(_ctx: AppContext, _args: PackedValue) -> WasmResult<PackedValue> {
+        Err(WasmError::new("nested call failure"))
+    }
+
+    #[test]
+    fn store_and_catch_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+        not_wasm::set_contract_method(OWNER_ID, "nested_store", fail_packed);
+        let args = NestedStoreArgs {
+            key: "foo",
+            fail: true,
+        };
+
+        let failed = not_wasm::call_wrap(store_and_catch, ctx, args).unwrap();
+
+        assert!(failed);
+        assert_eq!(not_wasm::get_account_data(OWNER_ID, "foo"), [1]);
+    }
+
     #[test]
     fn test_drand() {
         let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
@@ -726,4 +1565,61 @@ mod tests {
         let res = not_wasm::call_wrap(test_hf_drand, ctx, args).unwrap();
         assert_eq!(res, args / 2);
     }
+
+    #[test]
+    fn get_nan_bits_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let bits = not_wasm::call_wrap(get_nan_bits, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(bits.len(), 7);
+        assert!(bits[..6].iter().all(|b| f64::from_bits(*b).is_nan()));
+        assert!(f32::from_bits(bits[6] as u32).is_nan());
+    }
+
+    #[test]
+    fn get_alloc_offsets_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let offsets = not_wasm::call_wrap(get_alloc_offsets, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(offsets.len(), 5);
+        assert_eq!(offsets[0], 0);
+    }
+
+    #[test]
+    fn get_simd_disabled() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let res = not_wasm::call_wrap(get_simd, ctx, PackedValue::default()).unwrap();
+
+        assert_eq!(res, None);
+    }
+
+    #[test]
+    fn alloc_pages_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let res = not_wasm::call_wrap(alloc_pages, ctx, 16).unwrap();
+
+        assert_eq!(res, 16);
+    }
+
+    #[test]
+    fn recurse_to_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        let res = not_wasm::call_wrap(recurse_to, ctx, 100).unwrap();
+
+        assert_eq!(res, 100);
+    }
+
+    #[test]
+    fn store_bytes_test() {
+        let ctx = not_wasm::create_app_context(OWNER_ID, CALLER_ID);
+
+        not_wasm::call_wrap(store_bytes, ctx, 1000).unwrap();
+
+        assert_eq!(not_wasm::get_account_data(OWNER_ID, PROBE_KEY).len(), 1000);
+    }
 }
@@ -15,27 +15,40 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
 
-use crate::common;
+use crate::{common, memdb::MemDb};
 use glob::glob;
 use std::{
     path::PathBuf,
     sync::{Arc, Once},
     time::Duration,
 };
+#[cfg(feature = "rocksdb")]
 use tempfile::TempDir;
+#[cfg(feature = "rocksdb")]
+use trinci_core::db::RocksDb;
 use trinci_core::{
     base::serialize::{rmp_deserialize, rmp_serialize},
-    blockchain::{BlockConfig, BlockService, IsValidator, Message},
+    blockchain::{BlockConfig, BlockResponseReceiver, BlockService, Event, IsValidator, Message},
     crypto::{
         drand::SeedSource,
         ecdsa::{CurveId as EcdsaCurveId, KeyPair as EcdsaKeyPair},
         Hash, HashAlgorithm,
     },
-    db::{Db, DbFork, RocksDb},
+    db::{Db, DbFork},
     wm::WmLocal,
     Account, ErrorKind, KeyPair, Receipt, Transaction, SERVICE_ACCOUNT_ID,
 };
 
+/// Identifier of the produced blocks subscription.
+const SUBSCRIBER_ID: &str = "test-app";
+/// Network name of the test blocks.
+const NETWORK: &str = "skynet";
+/// Block timeout (seconds), a block is built when the pool reaches the
+/// threshold well before it expires.
+const BLOCK_TIMEOUT: u16 = 60;
+/// Maximum wait for a block service response or a produced block.
+const RECV_TIMEOUT: Duration = Duration::from_secs(120);
+
 const PRIVATE_KEY_BYTES: &str = "d7d90e5214f69e1297ca555815eefb5a540353561cc45e3be0db6b605f9f337140fae80101bb04e627cecb74556dead3";
 const PUBLIC_KEY_BYTES: &str = "045be4d28cc245b289885f7912f8ae1ef98463e5846646bcb211b445de2022cdcf22f08147fdd7f6276059c21af5b9b64d08339cd013b28d7cf898fd32e3d2fccfae8e421075f24d25d857dbc919ebfd09ccbc371f2ba77c5d7a7f6d62d856a24e";
 
@@ -47,9 +60,43 @@ pub fn create_keypair() -> KeyPair {
     )
 }
 
-pub struct TestApp {
-    pub block_svc: BlockService<RocksDb, WmLocal>,
-    pub path: PathBuf,
+/// Database backend used by `TestApp::new` and `TestApp::default`.
+#[cfg(feature = "rocksdb")]
+pub type DefaultDb = RocksDb;
+
+/// Database backend used by `TestApp::new` and `TestApp::default`.
+#[cfg(not(feature = "rocksdb"))]
+pub type DefaultDb = MemDb;
+
+/// Database backends the test application can be instanced with.
+pub trait TestDb: Db + Sized {
+    /// Open an empty database.
+    ///
+    /// Returns the database along with its folder, if any, to be removed on drop.
+    fn open() -> (Self, Option<PathBuf>);
+}
+
+#[cfg(feature = "rocksdb")]
+impl TestDb for RocksDb {
+    fn open() -> (Self, Option<PathBuf>) {
+        let path = TempDir::new().unwrap().into_path();
+        (RocksDb::new(&path), Some(path))
+    }
+}
+
+impl TestDb for MemDb {
+    fn open() -> (Self, Option<PathBuf>) {
+        (MemDb::new(), None)
+    }
+}
+
+pub struct TestApp<D: Db = DefaultDb> {
+    pub block_svc: BlockService<D, WmLocal>,
+    pub path: Option<PathBuf>,
+    /// Produced blocks notifications.
+    blocks: BlockResponseReceiver,
+    /// Transactions waiting for the next `mine_block`.
+    pending: Vec<Transaction>,
 }
 
 impl Default for TestApp {
@@ -73,7 +120,7 @@ fn is_validator_function_temporary() -> impl IsValidator {
 }
 
 // Register all the wasm module found in apps_path in the service account
-fn register_service_and_modules_on_service_account(apps_path: &str, db: &mut RocksDb) {
+fn register_service_and_modules_on_service_account<D: Db>(apps_path: &str, db: &mut D) {
     let mut fork = db.fork_create();
 
     let mut pattern = String::from(apps_path);
@@ -104,11 +151,29 @@ fn register_service_and_modules_on_service_account(apps_path: &str, db: &mut Roc
 }
 
 impl TestApp {
+    /// Instance the application with the default database backend.
     pub fn new(apps_path: &str) -> Self {
+        Self::with_backend(apps_path)
+    }
+}
+
+impl TestApp<MemDb> {
+    /// Instance the application with the in-memory database backend.
+    ///
+    /// The account state hash is the hash of the account data and not a
+    /// merkle root, thus the blocks hashes differ from the ones produced
+    /// with `RocksDb` or by a node: the tests shall not compare them.
+    pub fn in_memory() -> Self {
+        Self::with_backend(&common::apps_path())
+    }
+}
+
+impl<D: TestDb> TestApp<D> {
+    /// Instance the application with the `D` database backend.
+    pub fn with_backend(apps_path: &str) -> Self {
         logger_setup();
 
-        let path = TempDir::new().unwrap().into_path();
-        let mut db = RocksDb::new(&path);
+        let (mut db, path) = D::open();
 
         // Store the smart contracts on the db
         if !apps_path.is_empty() {
@@ -120,10 +185,12 @@ impl TestApp {
         let keypair = create_keypair();
         let account_id = keypair.public_key().to_account_id();
 
+        // The threshold is set by `mine_block` to the number of transactions
+        // of the batch, so that the blocks production is driven by it.
         let config = BlockConfig {
-            timeout: 1,
-            threshold: 42,
-            network: "skynet".to_owned(),
+            timeout: BLOCK_TIMEOUT,
+            threshold: 1,
+            network: NETWORK.to_owned(),
             keypair: Arc::new(keypair),
         };
 
@@ -149,18 +216,34 @@ impl TestApp {
 
         block_svc.start();
 
-        TestApp { block_svc, path }
+        let req = Message::Subscribe {
+            id: SUBSCRIBER_ID.to_owned(),
+            events: Event::BLOCK,
+        };
+        let blocks = block_svc.request_channel().send_sync(req).unwrap();
+
+        TestApp {
+            block_svc,
+            path,
+            blocks,
+            pending: Vec::new(),
+        }
     }
+}
 
+impl<D: Db> TestApp<D> {
     fn send_recv_packed(&self, requests: Vec<Message>) -> Vec<Message> {
-        const RECV_TIMEOUT: Duration = Duration::from_secs(3600);
+        let requests_len = requests.len();
         let req_chan = self.block_svc.request_channel();
         let buf = rmp_serialize(&requests).unwrap();
         let res_chan = req_chan.send_sync(Message::Packed { buf }).unwrap();
         match res_chan.recv_timeout_sync(RECV_TIMEOUT) {
             Ok(Message::Packed { buf }) => rmp_deserialize(&buf).unwrap(),
             Ok(res) => panic!("Unexpected block service response: {:?}", res),
-            Err(err) => panic!("Put transaction error: {}", err),
+            Err(err) => panic!(
+                "No block service response to {} requests within {:?}: {}",
+                requests_len, RECV_TIMEOUT, err
+            ),
         }
     }
 
@@ -206,27 +289,61 @@ impl TestApp {
         Some(responses)
     }
 
-    // Execute transactions set and wait for receipts.
-    pub fn exec_txs(&mut self, txs: Vec<Transaction>) -> Vec<Receipt> {
-        const MAX_TRIALS: usize = 42 * 2;
-        const RETRY_PERIOD: Duration = Duration::from_secs(3);
+    /// Add the transactions to the ones executed by the next `mine_block`.
+    pub fn push_txs(&mut self, txs: Vec<Transaction>) {
+        self.pending.extend(txs);
+    }
 
+    /// Execute the pending transactions in exactly one block.
+    ///
+    /// The block threshold is set to the number of pending transactions, so
+    /// that the block is built as soon as all of them are in the pool.
+    /// Returns the receipts once the block has been produced.
+    pub fn mine_block(&mut self) -> Vec<Receipt> {
+        let txs = std::mem::take(&mut self.pending);
+        if txs.is_empty() {
+            return vec![];
+        }
+        let count = txs.len();
+        self.block_svc
+            .set_block_config(NETWORK.to_owned(), count, BLOCK_TIMEOUT);
         let hashes = self.put_transactions(txs);
 
-        let mut max_trials = MAX_TRIALS;
-        loop {
-            match self.get_receipts(hashes.clone()) {
-                Some(receipts) => return receipts,
-                None => {
-                    if max_trials >= 1 {
-                        std::thread::sleep(RETRY_PERIOD);
-                        max_trials -= 1;
-                    } else {
-                        panic!("Timeout waiting for receipts");
+        let mut blocks = 0;
+        let receipts = loop {
+            match self.blocks.recv_timeout_sync(RECV_TIMEOUT) {
+                Ok(Message::GetBlockResponse { .. }) => {
+                    blocks += 1;
+                    if let Some(receipts) = self.get_receipts(hashes.clone()) {
+                        break receipts;
                     }
                 }
+                Ok(_) => (),
+                Err(err) => panic!(
+                    "No block with the {} pending transactions within {:?} ({} blocks produced): {}",
+                    count, RECV_TIMEOUT, blocks, err
+                ),
             }
-        }
+        };
+
+        assert_eq!(
+            blocks, 1,
+            "{} transactions mined in {} blocks",
+            count, blocks
+        );
+        let height = receipts[0].height;
+        assert!(
+            receipts.iter().all(|rx| rx.height == height),
+            "{} transactions mined in more than one block",
+            count
+        );
+        receipts
+    }
+
+    /// Execute the transactions in a new block and return their receipts.
+    pub fn exec_txs(&mut self, txs: Vec<Transaction>) -> Vec<Receipt> {
+        self.push_txs(txs);
+        self.mine_block()
     }
 
     pub fn account_data(&self, id: &str, key: &str) -> Option<Vec<u8>> {
@@ -256,14 +373,16 @@ impl TestApp {
     }
 }
 
-impl Drop for TestApp {
+impl<D: Db> Drop for TestApp<D> {
     fn drop(&mut self) {
         self.block_svc.stop();
-        std::fs::remove_dir_all(&self.path).unwrap_or_else(|err| {
-            println!(
-                "failed to remove temporary db folder '{:?}' ({})",
-                self.path, err
-            );
-        });
+        if let Some(path) = &self.path {
+            std::fs::remove_dir_all(path).unwrap_or_else(|err| {
+                println!(
+                    "failed to remove temporary db folder '{:?}' ({})",
+                    path, err
+                );
+            });
+        }
     }
 }
@@ -0,0 +1,133 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Asset contract client
+
+// The contract unit tests helpers, built by this crate tests, are not used.
+#[cfg_attr(test, allow(dead_code))]
+#[path = "../../../apps-rs/asset/src/types.rs"]
+pub mod types;
+
+use super::{CallResult, ContractClient};
+use crate::{
+    app::{DefaultDb, TestApp},
+    common::{self, AccountInfo},
+};
+use serde::Deserialize;
+use serde_value::value;
+use trinci_core::{base::serialize::rmp_deserialize, db::Db};
+use types::{
+    AddDelegationArgs, Asset, BurnArgs, InitArgs, LockArgs, LockType, MintArgs, TransferArgs,
+};
+
+/// Asset configuration returned by `stats`, owned version of the contract
+/// `AssetConfig`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct AssetStats {
+    /// Asset name.
+    pub name: String,
+    /// Account that has invoked the `init` method.
+    pub creator: String,
+    /// Accounts allowed to `mint` and `burn`.
+    pub authorized: Vec<String>,
+    /// Asset description.
+    pub description: String,
+    /// Asset public url.
+    pub url: String,
+    /// Max mintable units.
+    pub max_units: u64,
+    /// Minted units.
+    pub minted: u64,
+    /// Burned units.
+    pub burned: u64,
+}
+
+/// Asset contract client.
+pub struct AssetClient<'a, D: Db = DefaultDb> {
+    client: ContractClient<'a, D>,
+}
+
+impl<'a, D: Db> AssetClient<'a, D> {
+    /// Client of the `asset` contract bound to the `asset` account.
+    pub fn new(app: &'a mut TestApp<D>, asset: &str) -> Self {
+        let contract = common::app_hash("asset.wasm").unwrap();
+        AssetClient {
+            client: ContractClient::new(app, asset, contract),
+        }
+    }
+
+    pub fn init(&mut self, caller: &AccountInfo, args: InitArgs) -> CallResult<()> {
+        self.client.call(caller, "init", args)
+    }
+
+    pub fn mint(&mut self, caller: &AccountInfo, to: &str, units: u64) -> CallResult<()> {
+        self.client.call(caller, "mint", MintArgs { to, units })
+    }
+
+    pub fn burn(&mut self, caller: &AccountInfo, from: &str, units: u64) -> CallResult<()> {
+        self.client.call(caller, "burn", BurnArgs { from, units })
+    }
+
+    /// Returns the caller balance.
+    pub fn balance(&mut self, caller: &AccountInfo) -> CallResult<u64> {
+        self.client.call(caller, "balance", value!(null))
+    }
+
+    pub fn stats(&mut self, caller: &AccountInfo) -> CallResult<AssetStats> {
+        self.client.call(caller, "stats", value!(null))
+    }
+
+    pub fn transfer(
+        &mut self,
+        caller: &AccountInfo,
+        from: &str,
+        to: &str,
+        units: u64,
+    ) -> CallResult<()> {
+        let args = TransferArgs {
+            from,
+            to,
+            units,
+            data: None,
+        };
+        self.client.call(caller, "transfer", args)
+    }
+
+    pub fn lock(&mut self, caller: &AccountInfo, to: &str, lock: LockType) -> CallResult<()> {
+        self.client.call(caller, "lock", LockArgs { to, lock })
+    }
+
+    pub fn add_delegation(
+        &mut self,
+        caller: &AccountInfo,
+        delegate: &str,
+        units: u64,
+        to: Option<&str>,
+    ) -> CallResult<()> {
+        let args = AddDelegationArgs {
+            delegate,
+            units,
+            to,
+        };
+        self.client.call(caller, "add_delegation", args)
+    }
+
+    /// Returns the asset held by the `account`, read from the blockchain state.
+    pub fn asset(&self, account: &str) -> Option<Asset> {
+        let account = self.client.app().account(account)?;
+        rmp_deserialize(&account.load_asset(self.client.account())).ok()
+    }
+}
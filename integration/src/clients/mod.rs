@@ -0,0 +1,136 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Typed contracts clients
+//!
+//! Every client executes the methods of a contract, bound to an account, with
+//! the arguments types of the contract itself and decodes the returns.
+
+pub mod asset;
+pub mod pay_meal;
+pub mod split_payment;
+pub mod storage;
+
+pub use asset::AssetClient;
+pub use pay_meal::PayMealClient;
+pub use split_payment::SplitPaymentClient;
+pub use storage::StorageClient;
+
+use crate::{
+    app::{DefaultDb, TestApp},
+    common::{self, AccountInfo},
+};
+use serde::de::DeserializeOwned;
+use trinci_core::{base::serialize::rmp_deserialize, crypto::Hash, db::Db, Receipt};
+use trinci_sdk::Serializable;
+
+/// Contract method result: the decoded returns or the smart contract fault.
+pub type CallResult<T> = Result<T, String>;
+
+/// Client executing the methods of a contract bound to an account.
+pub struct ContractClient<'a, D: Db = DefaultDb> {
+    app: &'a mut TestApp<D>,
+    account: String,
+    contract: Hash,
+    last: Option<Receipt>,
+}
+
+impl<'a, D: Db> ContractClient<'a, D> {
+    /// Client of the `contract` bound to the `account`.
+    pub fn new(app: &'a mut TestApp<D>, account: &str, contract: Hash) -> Self {
+        ContractClient {
+            app,
+            account: account.to_owned(),
+            contract,
+            last: None,
+        }
+    }
+
+    /// Account the contract is bound to.
+    pub fn account(&self) -> &str {
+        &self.account
+    }
+
+    /// Application the methods are executed by.
+    pub fn app(&self) -> &TestApp<D> {
+        self.app
+    }
+
+    /// Execute a contract method, signed by the `caller`, in a new block.
+    pub fn call<A, R>(&mut self, caller: &AccountInfo, method: &str, args: A) -> CallResult<R>
+    where
+        A: Serializable,
+        R: DeserializeOwned,
+    {
+        let rx = self.exec(caller, method, args);
+        decode_receipt(rx)
+    }
+
+    /// Execute a contract method returning a raw buffer, not packed.
+    pub fn call_raw<A>(
+        &mut self,
+        caller: &AccountInfo,
+        method: &str,
+        args: A,
+    ) -> CallResult<Vec<u8>>
+    where
+        A: Serializable,
+    {
+        let rx = self.exec(caller, method, args);
+        match rx.success {
+            true => Ok(rx.returns.clone()),
+            false => Err(String::from_utf8_lossy(&rx.returns).to_string()),
+        }
+    }
+
+    /// Decode the data of the `index`-th event emitted by the last executed
+    /// method, `None` if missing or with another name.
+    pub fn event<T: DeserializeOwned>(&self, index: usize, name: &str) -> Option<T> {
+        let events = self.last.as_ref()?.events.as_ref()?;
+        let event = events.get(index)?;
+        if event.event_name != name {
+            return None;
+        }
+        rmp_deserialize(&event.event_data).ok()
+    }
+
+    /// Execute a contract method keeping its receipt.
+    fn exec<A: Serializable>(&mut self, caller: &AccountInfo, method: &str, args: A) -> &Receipt {
+        let tx = common::create_test_tx(
+            &self.account,
+            &caller.pub_key,
+            &caller.pvt_key,
+            self.contract,
+            method,
+            args,
+        );
+        let rx = self.app.exec_txs(vec![tx]).pop().unwrap();
+        self.last.insert(rx)
+    }
+}
+
+/// Decode the receipt returns into an owned type.
+fn decode_receipt<R: DeserializeOwned>(rx: &Receipt) -> CallResult<R> {
+    if !rx.success {
+        return Err(String::from_utf8_lossy(&rx.returns).to_string());
+    }
+    // The methods returning `()` may return an empty buffer.
+    let buf: &[u8] = match rx.returns.is_empty() {
+        true => &[0xc0],
+        false => &rx.returns,
+    };
+    rmp_deserialize(buf).map_err(|err| format!("unexpected method returns: {}", err))
+}
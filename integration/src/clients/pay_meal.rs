@@ -0,0 +1,170 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Pay meal contract client
+
+// The contract unit tests helpers, built by this crate tests, are not used.
+#[cfg_attr(test, allow(dead_code))]
+#[path = "../../../apps-rs/pay-meal/src/types.rs"]
+pub mod types;
+
+use super::{CallResult, ContractClient};
+use crate::{
+    app::{DefaultDb, TestApp},
+    common::{self, AccountInfo},
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_value::value;
+use std::collections::{BTreeMap, BTreeSet};
+use trinci_core::db::Db;
+use types::{InitArgs, PayForArgs};
+
+/// Event emitted when a customer share is paid.
+pub const PAID_EVENT: &str = "customer_paid";
+/// Event emitted when the bill is closed.
+pub const CLOSED_EVENT: &str = "bill_closed";
+/// Event emitted for each share refunded when the bill is cancelled.
+pub const REFUND_EVENT: &str = "refund_issued";
+
+/// Contract configuration returned by `get_info` to the restaurateur, owned
+/// version of the contract `InitArgs`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct BillConfig {
+    /// Account receiving the payments.
+    pub restaurateur: String,
+    /// Asset used for the payments.
+    pub asset: String,
+    /// Share to be paid by each customer.
+    pub part: u64,
+    /// Customers list, `true` when the share has been paid.
+    pub customers: BTreeMap<String, bool>,
+    /// Contract status: "open", "close" or "cancelled".
+    pub status: String,
+    /// Account that paid each customer share.
+    #[serde(default)]
+    pub payers: BTreeMap<String, String>,
+    /// Customers whose share has been collected.
+    #[serde(default)]
+    pub collected: BTreeSet<String>,
+}
+
+/// Aggregate state of the contract returned by `public_summary`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct BillSummary {
+    /// Asset used for the payments.
+    pub asset: String,
+    /// Share to be paid by each customer.
+    pub part: u64,
+    /// Number of customers.
+    pub customers: u64,
+    /// Number of customers whose share has been paid.
+    pub paid: u64,
+    /// Whole bill amount.
+    pub total: u64,
+    /// Contract status: "open", "close" or "cancelled".
+    pub status: String,
+}
+
+/// Customer view returned by `get_info` to a customer.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct CustomerView {
+    /// Account receiving the payments.
+    pub restaurateur: String,
+    /// Caller account.
+    pub customer: String,
+    /// `true` when the caller share has been paid.
+    pub paid: bool,
+    /// Account that paid the caller share.
+    pub payer: Option<String>,
+    /// Aggregate state of the contract.
+    pub summary: BillSummary,
+}
+
+/// Data of the `customer_paid` and `refund_issued` events.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct SharePayment {
+    /// Customer whose share has been paid or refunded.
+    pub customer: String,
+    /// Account that paid the share, receiving the refund.
+    pub payer: String,
+    /// Paid or refunded amount.
+    pub units: u64,
+}
+
+/// Data of the `bill_closed` event.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct BillClosed {
+    /// Account receiving the amount.
+    pub restaurateur: String,
+    /// Amount swept to the restaurateur.
+    pub amount: u64,
+    /// Contract status after the close.
+    pub status: String,
+}
+
+/// Pay meal contract client.
+pub struct PayMealClient<'a, D: Db = DefaultDb> {
+    client: ContractClient<'a, D>,
+}
+
+impl<'a, D: Db> PayMealClient<'a, D> {
+    /// Client of the `pay_meal` contract bound to the `account`.
+    pub fn new(app: &'a mut TestApp<D>, account: &str) -> Self {
+        let contract = common::app_hash("pay_meal.wasm").unwrap();
+        PayMealClient {
+            client: ContractClient::new(app, account, contract),
+        }
+    }
+
+    pub fn init(&mut self, caller: &AccountInfo, args: InitArgs) -> CallResult<()> {
+        self.client.call(caller, "init", args)
+    }
+
+    /// Returns the whole configuration, the caller shall be the restaurateur.
+    pub fn config(&mut self, caller: &AccountInfo) -> CallResult<BillConfig> {
+        self.client.call(caller, "get_info", value!(null))
+    }
+
+    /// Returns the caller entry, the caller shall be a customer.
+    pub fn customer_info(&mut self, caller: &AccountInfo) -> CallResult<CustomerView> {
+        self.client.call(caller, "get_info", value!(null))
+    }
+
+    pub fn public_summary(&mut self, caller: &AccountInfo) -> CallResult<BillSummary> {
+        self.client.call(caller, "public_summary", value!(null))
+    }
+
+    pub fn apply(&mut self, caller: &AccountInfo) -> CallResult<()> {
+        self.client.call(caller, "apply", value!(null))
+    }
+
+    pub fn pay_for(&mut self, caller: &AccountInfo, customer: &str) -> CallResult<()> {
+        self.client.call(caller, "pay_for", PayForArgs { customer })
+    }
+
+    pub fn close(&mut self, caller: &AccountInfo) -> CallResult<()> {
+        self.client.call(caller, "close", value!(null))
+    }
+
+    pub fn cancel(&mut self, caller: &AccountInfo) -> CallResult<()> {
+        self.client.call(caller, "cancel", value!(null))
+    }
+
+    /// Returns the data of the `index`-th event emitted by the last method.
+    pub fn event<T: DeserializeOwned>(&self, index: usize, name: &str) -> Option<T> {
+        self.client.event(index, name)
+    }
+}
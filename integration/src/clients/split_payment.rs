@@ -0,0 +1,94 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Split payment contract client
+
+// The contract unit tests helpers, built by this crate tests, are not used.
+#[cfg_attr(test, allow(dead_code))]
+#[path = "../../../apps-rs/split-payment/src/types.rs"]
+pub mod types;
+
+use super::{CallResult, ContractClient};
+use crate::{
+    app::{DefaultDb, TestApp},
+    common::{self, AccountInfo},
+};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use trinci_core::db::Db;
+use types::{BillArgs, OpenArgs, PayForArgs};
+
+/// Bill returned by `get_info`, owned version of the contract `Bill`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct BillStatus {
+    /// Account collecting the payments.
+    pub merchant: String,
+    /// Asset used for the payments.
+    pub asset: String,
+    /// Share to be paid by each participant.
+    pub part: u64,
+    /// Participants list, `true` when the share has been paid.
+    pub participants: BTreeMap<String, bool>,
+    /// Account that paid each participant share.
+    pub payers: BTreeMap<String, String>,
+    /// Units paid and not yet transferred to the merchant.
+    pub collected: u64,
+    /// Bill status: "open", "close" or "cancelled".
+    pub status: String,
+    /// Participants whose share has been transferred to the merchant.
+    #[serde(default)]
+    pub released: BTreeSet<String>,
+}
+
+/// Split payment contract client.
+pub struct SplitPaymentClient<'a, D: Db = DefaultDb> {
+    client: ContractClient<'a, D>,
+}
+
+impl<'a, D: Db> SplitPaymentClient<'a, D> {
+    /// Client of the `split_payment` contract bound to the `account`.
+    pub fn new(app: &'a mut TestApp<D>, account: &str) -> Self {
+        let contract = common::app_hash("split_payment.wasm").unwrap();
+        SplitPaymentClient {
+            client: ContractClient::new(app, account, contract),
+        }
+    }
+
+    pub fn open(&mut self, caller: &AccountInfo, args: OpenArgs) -> CallResult<()> {
+        self.client.call(caller, "open", args)
+    }
+
+    pub fn apply(&mut self, caller: &AccountInfo, id: &str) -> CallResult<()> {
+        self.client.call(caller, "apply", BillArgs { id })
+    }
+
+    pub fn pay_for(&mut self, caller: &AccountInfo, id: &str, participant: &str) -> CallResult<()> {
+        self.client
+            .call(caller, "pay_for", PayForArgs { id, participant })
+    }
+
+    pub fn get_info(&mut self, caller: &AccountInfo, id: &str) -> CallResult<BillStatus> {
+        self.client.call(caller, "get_info", BillArgs { id })
+    }
+
+    pub fn close(&mut self, caller: &AccountInfo, id: &str) -> CallResult<()> {
+        self.client.call(caller, "close", BillArgs { id })
+    }
+
+    pub fn cancel(&mut self, caller: &AccountInfo, id: &str) -> CallResult<()> {
+        self.client.call(caller, "cancel", BillArgs { id })
+    }
+}
@@ -0,0 +1,483 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Storage contract client
+
+// The contract unit tests helpers, built by this crate tests, are not used.
+#[cfg_attr(test, allow(dead_code))]
+#[path = "../../../apps-rs/storage/src/types.rs"]
+pub mod types;
+
+use super::{CallResult, ContractClient};
+use crate::{
+    app::{DefaultDb, TestApp},
+    common::{self, AccountInfo},
+};
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+use serde_value::value;
+use std::collections::BTreeMap;
+use trinci_core::db::Db;
+use trinci_sdk::Value;
+use types::{
+    AddOrderArgs, AddRecipientArgs, BalancesArgs, BatchArgs, BeginUploadArgs, BlobArgs, Envelope,
+    FieldArgs, GetSpendsArgs, GrantArgs, ListKeysArgs, LoadChunkArgs, LoadDataArgs, LoadManyArgs,
+    MultiTransferArgs, NotarizeArgs, OperatorArgs, Permissions, PutChunkArgs, Quota,
+    RemoveDataArgs, RevokeArgs, RevokeRecipientArgs, SetFieldArgs, SetOperatorArgs, StoreDataArgs,
+    StoreDataIfVersionArgs, StoreEnvelopeArgs, TransferArgs, VerifyArgs,
+};
+
+/// Page of keys returned by `list_keys`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct KeysPage {
+    /// Keys of the page, in lexicographic order.
+    pub keys: Vec<String>,
+    /// Cursor to get the next page, `None` on the last page.
+    pub cursor: Option<String>,
+}
+
+/// Data with its version returned by `load_data_versioned`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct VersionedValue {
+    /// Version of the value (0 if never written).
+    pub version: u64,
+    /// Stored data.
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// Result of a single transfer of `multi_transfer`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct TransferOutcome {
+    /// Destination account-id.
+    pub to: String,
+    /// Transferred asset.
+    pub asset: String,
+    /// Transferred amount.
+    pub units: u64,
+    /// Account balance of the asset after the transfer.
+    pub balance: u64,
+}
+
+/// Operator spend log record returned by `get_spends`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct OperatorSpend {
+    /// Operator account-id.
+    pub operator: String,
+    /// Destination account-id.
+    pub to: String,
+    /// Transferred asset.
+    pub asset: String,
+    /// Transferred amount.
+    pub units: u64,
+    /// Block time of the transfer.
+    pub timestamp: u64,
+}
+
+/// Notarization record returned by `verify`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct Notarization {
+    /// Account that notarized the document.
+    pub submitter: String,
+    /// Block time of the notarization.
+    pub timestamp: u64,
+    /// Optional information about the document.
+    pub metadata: Option<String>,
+}
+
+/// Account data limits, a zero limit is not enforced.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct QuotaLimits {
+    /// Maximum number of data keys.
+    pub max_keys: u64,
+    /// Maximum size of a single value in bytes.
+    pub max_value_size: u64,
+    /// Maximum size of all the values in bytes.
+    pub max_total_size: u64,
+}
+
+/// Account data usage.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct QuotaUsage {
+    /// Number of data keys.
+    pub keys: u64,
+    /// Size of all the values in bytes.
+    pub total_size: u64,
+}
+
+/// Quota and usage returned by `get_quota`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct QuotaReport {
+    /// Current quota (all zeros if not set).
+    pub quota: QuotaLimits,
+    /// Current usage.
+    pub usage: QuotaUsage,
+}
+
+/// Committed blob information returned by `blob_info`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct StoredBlob {
+    /// Blob size in bytes.
+    pub size: u64,
+    /// Size of the chunks the blob is stored with.
+    pub chunk_size: u32,
+    /// Number of stored chunks.
+    pub chunks: u32,
+}
+
+/// Envelope returned by `load_envelope`, owned version of the contract
+/// `Envelope`.
+#[derive(Deserialize, Debug, PartialEq, Clone)]
+pub struct OpenEnvelope {
+    /// Identifier of the encryption algorithm.
+    pub algorithm: String,
+    /// Encrypted data.
+    #[serde(with = "serde_bytes")]
+    pub ciphertext: Vec<u8>,
+    /// Content key wrapped for each recipient account.
+    pub keys: BTreeMap<String, ByteBuf>,
+}
+
+/// Storage contract client.
+pub struct StorageClient<'a, D: Db = DefaultDb> {
+    client: ContractClient<'a, D>,
+}
+
+impl<'a, D: Db> StorageClient<'a, D> {
+    /// Client of the `storage` contract bound to the `account`.
+    pub fn new(app: &'a mut TestApp<D>, account: &str) -> Self {
+        let contract = common::app_hash("storage.wasm").unwrap();
+        StorageClient {
+            client: ContractClient::new(app, account, contract),
+        }
+    }
+
+    /// Returns the raw data stored under the `key`, empty if missing.
+    pub fn load_data(&mut self, caller: &AccountInfo, key: &str) -> CallResult<Vec<u8>> {
+        self.client
+            .call_raw(caller, "load_data", LoadDataArgs { key })
+    }
+
+    pub fn store_data(&mut self, caller: &AccountInfo, key: &str, data: &[u8]) -> CallResult<()> {
+        let args = StoreDataArgs {
+            key,
+            data,
+            force: false,
+        };
+        self.client.call(caller, "store_data", args)
+    }
+
+    /// Store the data overwriting a protected key.
+    pub fn force_store_data(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        data: &[u8],
+    ) -> CallResult<()> {
+        let args = StoreDataArgs {
+            key,
+            data,
+            force: true,
+        };
+        self.client.call(caller, "store_data", args)
+    }
+
+    pub fn remove_data(&mut self, caller: &AccountInfo, key: &str) -> CallResult<()> {
+        let args = RemoveDataArgs { key, force: false };
+        self.client.call(caller, "remove_data", args)
+    }
+
+    pub fn load_data_versioned(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+    ) -> CallResult<VersionedValue> {
+        self.client
+            .call(caller, "load_data_versioned", LoadDataArgs { key })
+    }
+
+    /// Returns the new version of the value.
+    pub fn store_data_if_version(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        data: &[u8],
+        expected_version: u64,
+    ) -> CallResult<u64> {
+        let args = StoreDataIfVersionArgs {
+            key,
+            data,
+            expected_version,
+        };
+        self.client.call(caller, "store_data_if_version", args)
+    }
+
+    pub fn list_keys(
+        &mut self,
+        caller: &AccountInfo,
+        prefix: &str,
+        cursor: Option<&str>,
+        limit: u32,
+    ) -> CallResult<KeysPage> {
+        let args = ListKeysArgs {
+            prefix,
+            cursor,
+            limit,
+        };
+        self.client.call(caller, "list_keys", args)
+    }
+
+    /// Returns the data of the `keys`, in the same order.
+    pub fn load_many(&mut self, caller: &AccountInfo, keys: Vec<&str>) -> CallResult<Vec<Vec<u8>>> {
+        let values: Vec<ByteBuf> = self
+            .client
+            .call(caller, "load_many", LoadManyArgs { keys })?;
+        Ok(values.into_iter().map(ByteBuf::into_vec).collect())
+    }
+
+    pub fn batch(&mut self, caller: &AccountInfo, args: BatchArgs) -> CallResult<()> {
+        self.client.call(caller, "batch", args)
+    }
+
+    pub fn get_field(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        path: Vec<&str>,
+    ) -> CallResult<Value> {
+        self.client
+            .call(caller, "get_field", FieldArgs { key, path })
+    }
+
+    pub fn set_field(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        path: Vec<&str>,
+        value: Value,
+    ) -> CallResult<()> {
+        let args = SetFieldArgs { key, path, value };
+        self.client.call(caller, "set_field", args)
+    }
+
+    pub fn delete_field(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        path: Vec<&str>,
+    ) -> CallResult<()> {
+        self.client
+            .call(caller, "delete_field", FieldArgs { key, path })
+    }
+
+    pub fn append(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        path: Vec<&str>,
+        value: Value,
+    ) -> CallResult<()> {
+        let args = SetFieldArgs { key, path, value };
+        self.client.call(caller, "append", args)
+    }
+
+    pub fn store_envelope(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        envelope: Envelope,
+    ) -> CallResult<()> {
+        let args = StoreEnvelopeArgs {
+            key,
+            envelope,
+            force: false,
+        };
+        self.client.call(caller, "store_envelope", args)
+    }
+
+    pub fn load_envelope(&mut self, caller: &AccountInfo, key: &str) -> CallResult<OpenEnvelope> {
+        self.client
+            .call(caller, "load_envelope", LoadDataArgs { key })
+    }
+
+    pub fn add_recipient(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        recipient: &str,
+        wrapped_key: &[u8],
+    ) -> CallResult<()> {
+        let args = AddRecipientArgs {
+            key,
+            recipient,
+            wrapped_key,
+        };
+        self.client.call(caller, "add_recipient", args)
+    }
+
+    pub fn revoke_recipient(
+        &mut self,
+        caller: &AccountInfo,
+        key: &str,
+        recipient: &str,
+    ) -> CallResult<()> {
+        let args = RevokeRecipientArgs { key, recipient };
+        self.client.call(caller, "revoke_recipient", args)
+    }
+
+    /// Returns `false` if the blob is already stored.
+    pub fn begin_upload(
+        &mut self,
+        caller: &AccountInfo,
+        hash: &[u8],
+        size: u64,
+        chunks: u32,
+    ) -> CallResult<bool> {
+        let args = BeginUploadArgs { hash, size, chunks };
+        self.client.call(caller, "begin_upload", args)
+    }
+
+    pub fn put_chunk(
+        &mut self,
+        caller: &AccountInfo,
+        hash: &[u8],
+        index: u32,
+        data: &[u8],
+    ) -> CallResult<()> {
+        let args = PutChunkArgs { hash, index, data };
+        self.client.call(caller, "put_chunk", args)
+    }
+
+    pub fn commit_upload(&mut self, caller: &AccountInfo, hash: &[u8]) -> CallResult<()> {
+        self.client.call(caller, "commit_upload", BlobArgs { hash })
+    }
+
+    pub fn blob_info(&mut self, caller: &AccountInfo, hash: &[u8]) -> CallResult<StoredBlob> {
+        self.client.call(caller, "blob_info", BlobArgs { hash })
+    }
+
+    /// Returns the raw bytes of the blob range.
+    pub fn load_chunk(
+        &mut self,
+        caller: &AccountInfo,
+        hash: &[u8],
+        offset: u64,
+        length: u32,
+    ) -> CallResult<Vec<u8>> {
+        let args = LoadChunkArgs {
+            hash,
+            offset,
+            length,
+        };
+        self.client.call_raw(caller, "load_chunk", args)
+    }
+
+    pub fn notarize(
+        &mut self,
+        caller: &AccountInfo,
+        hash: &[u8],
+        metadata: Option<&str>,
+    ) -> CallResult<()> {
+        self.client
+            .call(caller, "notarize", NotarizeArgs { hash, metadata })
+    }
+
+    pub fn verify(&mut self, caller: &AccountInfo, hash: &[u8]) -> CallResult<Notarization> {
+        self.client.call(caller, "verify", VerifyArgs { hash })
+    }
+
+    pub fn set_quota(&mut self, caller: &AccountInfo, quota: Quota) -> CallResult<()> {
+        self.client.call(caller, "set_quota", quota)
+    }
+
+    pub fn get_quota(&mut self, caller: &AccountInfo) -> CallResult<QuotaReport> {
+        self.client.call(caller, "get_quota", value!(null))
+    }
+
+    pub fn grant(
+        &mut self,
+        caller: &AccountInfo,
+        pattern: &str,
+        account: &str,
+        permissions: Permissions,
+    ) -> CallResult<()> {
+        let args = GrantArgs {
+            pattern,
+            account,
+            permissions,
+        };
+        self.client.call(caller, "grant", args)
+    }
+
+    pub fn revoke(&mut self, caller: &AccountInfo, pattern: &str, account: &str) -> CallResult<()> {
+        self.client
+            .call(caller, "revoke", RevokeArgs { pattern, account })
+    }
+
+    /// Returns the caller balance of each asset, in the same order.
+    pub fn balances(&mut self, caller: &AccountInfo, assets: Vec<&str>) -> CallResult<Vec<u64>> {
+        self.client
+            .call(caller, "balances", BalancesArgs { assets })
+    }
+
+    pub fn transfer(
+        &mut self,
+        caller: &AccountInfo,
+        to: &str,
+        asset: &str,
+        units: u64,
+    ) -> CallResult<()> {
+        let args = TransferArgs { to, asset, units };
+        self.client.call(caller, "transfer", args)
+    }
+
+    pub fn multi_transfer(
+        &mut self,
+        caller: &AccountInfo,
+        transfers: Vec<TransferArgs>,
+    ) -> CallResult<Vec<TransferOutcome>> {
+        let args = MultiTransferArgs { transfers };
+        self.client.call(caller, "multi_transfer", args)
+    }
+
+    pub fn add_order(&mut self, caller: &AccountInfo, args: AddOrderArgs) -> CallResult<()> {
+        self.client.call(caller, "add_order", args)
+    }
+
+    /// Returns the identifiers of the executed orders.
+    pub fn execute_due(&mut self, caller: &AccountInfo) -> CallResult<Vec<String>> {
+        self.client.call(caller, "execute_due", value!(null))
+    }
+
+    pub fn set_operator(&mut self, caller: &AccountInfo, args: SetOperatorArgs) -> CallResult<()> {
+        self.client.call(caller, "set_operator", args)
+    }
+
+    pub fn revoke_operator(&mut self, caller: &AccountInfo, account: &str) -> CallResult<()> {
+        self.client
+            .call(caller, "revoke_operator", OperatorArgs { account })
+    }
+
+    pub fn get_spends(
+        &mut self,
+        caller: &AccountInfo,
+        start: u64,
+        limit: u32,
+    ) -> CallResult<Vec<OperatorSpend>> {
+        let args = GetSpendsArgs { start, limit };
+        self.client.call(caller, "get_spends", args)
+    }
+}
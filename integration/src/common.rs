@@ -162,6 +162,28 @@ pub fn create_test_tx(
     Transaction::UnitTransaction(SignedTransaction { data, signature })
 }
 
+/// Create a test transaction with the arguments already serialized.
+///
+/// Allows to send arbitrary, even malformed, buffers to the contracts.
+pub fn create_raw_test_tx(
+    id: &str,
+    public_key: &str,
+    private_key: &str,
+    target: Hash,
+    method: &str,
+    args: Vec<u8>,
+) -> Transaction {
+    let mut data = create_test_tx_data(id, public_key, target, method, value!(null));
+    if let TransactionData::V1(ref mut data) = data {
+        data.args = args;
+    }
+    let keypair = p384_hex_keypair_to_keypair(public_key, private_key);
+    let buf = trinci_core::base::serialize::rmp_serialize(&data).unwrap();
+    let signature = keypair.sign(&buf).unwrap();
+
+    Transaction::UnitTransaction(SignedTransaction { data, signature })
+}
+
 pub fn create_bulk_root_tx(
     id: &str,
     public_key: &str,
@@ -360,7 +382,8 @@ pub fn p384_hex_key_to_public_key(public_key: &str) -> PublicKey {
     PublicKey::Ecdsa(ecdsa_public_key)
 }
 
-fn p384_hex_keypair_to_keypair(public_key: &str, private_key: &str) -> KeyPair {
+/// Utility function to get a key pair from hex bytes.
+pub fn p384_hex_keypair_to_keypair(public_key: &str, private_key: &str) -> KeyPair {
     let public_bytes = hex::decode(public_key).unwrap();
     let private_bytes = hex::decode(private_key).unwrap();
     let ecdsa_keypair =
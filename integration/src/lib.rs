@@ -16,6 +16,9 @@
 // along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
 
 pub mod app;
+pub mod clients;
 pub mod common;
+pub mod memdb;
 
 pub use app::TestApp;
+pub use memdb::MemDb;
@@ -0,0 +1,246 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! In-memory database backend
+//!
+//! Keeps all the tables in memory, it is faster to instance than `RocksDb`
+//! and does not require its native build dependencies. The account state hash
+//! is the hash of the account data instead of a merkle root, thus the blocks
+//! hashes are not the ones produced by a node.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+use trinci_core::{
+    base::serialize::rmp_serialize,
+    crypto::{Hash, HashAlgorithm},
+    db::{Db, DbFork},
+    Account, Block, Receipt, Result, Transaction,
+};
+
+/// Database tables.
+///
+/// Cloning the tables for a fork or a backup copies every map, the data
+/// buffers, transactions, receipts and blocks are behind an `Arc` so that
+/// only their pointers are copied.
+#[derive(Clone, Default)]
+struct Tables {
+    accounts: HashMap<String, Account>,
+    data: HashMap<String, BTreeMap<String, Arc<Vec<u8>>>>,
+    transactions: HashMap<Vec<u8>, Arc<Transaction>>,
+    receipts: HashMap<Vec<u8>, Arc<Receipt>>,
+    blocks: HashMap<u64, Arc<Block>>,
+    transactions_hashes: HashMap<u64, Vec<Hash>>,
+    receipts_hashes: HashMap<u64, Vec<Hash>>,
+    configuration: HashMap<String, Vec<u8>>,
+}
+
+impl Tables {
+    fn load_account_data(&self, id: &str, key: &str) -> Option<Vec<u8>> {
+        self.data
+            .get(id)
+            .and_then(|data| data.get(key))
+            .map(|buf| buf.to_vec())
+    }
+
+    fn load_account_keys(&self, id: &str) -> Vec<String> {
+        self.data
+            .get(id)
+            .map(|data| data.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+/// In-memory database.
+#[derive(Default)]
+pub struct MemDb {
+    tables: Tables,
+}
+
+impl MemDb {
+    /// Create an empty database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Db for MemDb {
+    type DbForkType = MemDbFork;
+
+    fn load_account(&self, id: &str) -> Option<Account> {
+        self.tables.accounts.get(id).cloned()
+    }
+
+    fn load_account_data(&self, id: &str, key: &str) -> Option<Vec<u8>> {
+        self.tables.load_account_data(id, key)
+    }
+
+    fn contains_transaction(&self, key: &Hash) -> bool {
+        self.tables.transactions.contains_key(key.as_bytes())
+    }
+
+    fn load_transaction(&self, key: &Hash) -> Option<Transaction> {
+        self.tables
+            .transactions
+            .get(key.as_bytes())
+            .map(|tx| tx.as_ref().clone())
+    }
+
+    fn load_receipt(&self, key: &Hash) -> Option<Receipt> {
+        self.tables
+            .receipts
+            .get(key.as_bytes())
+            .map(|rx| rx.as_ref().clone())
+    }
+
+    fn load_block(&self, height: u64) -> Option<Block> {
+        self.tables
+            .blocks
+            .get(&height)
+            .map(|block| block.as_ref().clone())
+    }
+
+    fn load_transactions_hashes(&self, height: u64) -> Option<Vec<Hash>> {
+        self.tables.transactions_hashes.get(&height).cloned()
+    }
+
+    fn load_receipts_hashes(&self, height: u64) -> Option<Vec<Hash>> {
+        self.tables.receipts_hashes.get(&height).cloned()
+    }
+
+    fn fork_create(&mut self) -> MemDbFork {
+        MemDbFork {
+            tables: self.tables.clone(),
+            backups: BTreeMap::new(),
+        }
+    }
+
+    /// The fork replaces the database content: as with `RocksDb` only one
+    /// fork at a time is expected to be merged.
+    fn fork_merge(&mut self, fork: MemDbFork) -> Result<()> {
+        self.tables = fork.tables;
+        Ok(())
+    }
+
+    fn load_account_keys(&self, id: &str) -> Vec<String> {
+        self.tables.load_account_keys(id)
+    }
+
+    fn load_configuration(&self, id: &str) -> Option<Vec<u8>> {
+        self.tables.configuration.get(id).cloned()
+    }
+}
+
+/// In-memory database fork.
+pub struct MemDbFork {
+    tables: Tables,
+    /// Tables backups, indexed by level.
+    backups: BTreeMap<u8, Tables>,
+}
+
+impl DbFork for MemDbFork {
+    fn state_hash(&self, id: &str) -> Hash {
+        match self.tables.data.get(id) {
+            Some(data) if !data.is_empty() => {
+                let entries: Vec<(&String, &[u8])> = data
+                    .iter()
+                    .map(|(key, buf)| (key, buf.as_slice()))
+                    .collect();
+                let buf = rmp_serialize(&entries).unwrap();
+                Hash::from_data(HashAlgorithm::Sha256, &buf)
+            }
+            _ => Hash::default(),
+        }
+    }
+
+    fn load_account(&self, id: &str) -> Option<Account> {
+        self.tables.accounts.get(id).cloned()
+    }
+
+    fn store_account(&mut self, account: Account) {
+        self.tables.accounts.insert(account.id.clone(), account);
+    }
+
+    fn load_account_data(&self, id: &str, key: &str) -> Option<Vec<u8>> {
+        self.tables.load_account_data(id, key)
+    }
+
+    fn store_account_data(&mut self, id: &str, key: &str, data: Vec<u8>) {
+        self.tables
+            .data
+            .entry(id.to_owned())
+            .or_default()
+            .insert(key.to_owned(), Arc::new(data));
+    }
+
+    fn delete_account_data(&mut self, id: &str, key: &str) {
+        if let Some(data) = self.tables.data.get_mut(id) {
+            data.remove(key);
+        }
+    }
+
+    fn load_account_keys(&self, id: &str) -> Vec<String> {
+        self.tables.load_account_keys(id)
+    }
+
+    fn store_transaction(&mut self, hash: &Hash, transaction: Transaction) {
+        self.tables
+            .transactions
+            .insert(hash.as_bytes().to_vec(), Arc::new(transaction));
+    }
+
+    fn store_receipt(&mut self, hash: &Hash, receipt: Receipt) {
+        self.tables
+            .receipts
+            .insert(hash.as_bytes().to_vec(), Arc::new(receipt));
+    }
+
+    fn store_block(&mut self, block: Block) {
+        self.tables
+            .blocks
+            .insert(block.data.height, Arc::new(block));
+    }
+
+    fn store_transactions_hashes(&mut self, height: u64, hashes: Vec<Hash>) {
+        self.tables.transactions_hashes.insert(height, hashes);
+    }
+
+    fn store_receipts_hashes(&mut self, height: u64, hashes: Vec<Hash>) {
+        self.tables.receipts_hashes.insert(height, hashes);
+    }
+
+    /// Backup the tables at `level`, the backups of the higher levels are
+    /// dropped.
+    fn flush_backup(&mut self, level: u8) {
+        self.backups.retain(|backup_level, _| *backup_level < level);
+        self.backups.insert(level, self.tables.clone());
+    }
+
+    /// Restore the tables backed up at `level`, the backups of the higher
+    /// levels are dropped.
+    fn flush_restore(&mut self, level: u8) {
+        self.backups
+            .retain(|backup_level, _| *backup_level <= level);
+        if let Some(tables) = self.backups.get(&level) {
+            self.tables = tables.clone();
+        }
+    }
+
+    fn store_configuration(&mut self, id: &str, config: Vec<u8>) {
+        self.tables.configuration.insert(id.to_owned(), config);
+    }
+}
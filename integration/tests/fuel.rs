@@ -0,0 +1,221 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fuel consumption benchmarks
+//!
+//! Every benchmark executes a test contract method with a growing size and
+//! checks that the burned fuel grows linearly with the size. The burned fuel
+//! is then compared with the records of the `fuel_baseline` directory: a run
+//! burning more than `REGRESSION_THRESHOLD` percent over its record fails.
+//! A run without a record fails, set `FUEL_BASELINE_UPDATE=1` to record
+//! them all again and commit the updated records.
+//!
+//! The benchmarks are ignored by default, until their records are committed:
+//! `FUEL_BASELINE_UPDATE=1 cargo test --test fuel -- --ignored`.
+use integration::{
+    common::{self, *},
+    TestApp,
+};
+use lazy_static::lazy_static;
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::PathBuf,
+};
+use trinci_core::{crypto::Hash, Transaction};
+
+const TEST_ALIAS: &str = "Test";
+
+/// Number of runs of each benchmark, the size of the run `i` is `i * step`.
+const RUNS: u64 = 4;
+/// Maximum deviation of the fuel burned by each step from the average one (percent).
+const LINEARITY_TOLERANCE: u64 = 5;
+/// Maximum fuel increase with respect to the baseline (percent).
+const REGRESSION_THRESHOLD: u64 = 10;
+/// Environment variable requesting to record the baseline again.
+const BASELINE_UPDATE_VAR: &str = "FUEL_BASELINE_UPDATE";
+
+lazy_static! {
+    static ref ACCOUNTS_INFO: HashMap<&'static str, AccountInfo> = {
+        let mut map = HashMap::new();
+        map.insert(TEST_ALIAS, AccountInfo::new(PUB_KEY1, PVT_KEY1, ""));
+        map
+    };
+    static ref TEST_APP_HASH: Hash = app_hash("test.wasm").unwrap();
+}
+
+fn fuel_tx(test_info: &AccountInfo, method: &str, n: u64) -> Transaction {
+    let args = value!(n);
+    common::create_test_tx(
+        &test_info.id,
+        &test_info.pub_key,
+        &test_info.pvt_key,
+        *TEST_APP_HASH,
+        method,
+        args,
+    )
+}
+
+/// Execute the benchmark method with size `n` on a fresh application.
+///
+/// Returns the burned fuel.
+fn run_once(method: &str, n: u64) -> u64 {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let txs = vec![
+        // 0. Bind the contract to the account, so that the run is not charged for it.
+        fuel_tx(test_info, method, 0),
+        // 1. Run the benchmark.
+        fuel_tx(test_info, method, n),
+    ];
+
+    // Instance the application, a previous run shall not change the account state.
+    let mut app = TestApp::default();
+    let rxs = app.exec_txs(txs);
+
+    let rx = &rxs[1];
+    assert!(
+        rx.success,
+        "{}({}) failed: {}",
+        method,
+        n,
+        String::from_utf8_lossy(&rx.returns)
+    );
+    rx.burned_fuel
+}
+
+/// Execute the benchmark method with sizes multiple of `step`.
+///
+/// Returns the size and the burned fuel of each run.
+fn run_benchmark(method: &str, step: u64) -> Vec<(u64, u64)> {
+    (1..=RUNS)
+        .map(|i| i * step)
+        .map(|n| (n, run_once(method, n)))
+        .collect()
+}
+
+/// Check that every step burns the same fuel, within the tolerance.
+fn check_linear(method: &str, runs: &[(u64, u64)]) {
+    let steps: Vec<u64> = runs
+        .windows(2)
+        .map(|w| {
+            w[1].1
+                .checked_sub(w[0].1)
+                .unwrap_or_else(|| panic!("{} fuel decreased: {:?}", method, runs))
+        })
+        .collect();
+    let average = steps.iter().sum::<u64>() / steps.len() as u64;
+    assert!(average > 0, "{} fuel does not grow: {:?}", method, runs);
+
+    for step in steps {
+        assert!(
+            step.abs_diff(average) * 100 <= average * LINEARITY_TOLERANCE,
+            "{} fuel is not linear: {:?}",
+            method,
+            runs
+        );
+    }
+}
+
+fn baseline_path(method: &str) -> PathBuf {
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("fuel_baseline");
+    path.push(format!("{}.txt", method));
+    path
+}
+
+/// Load the baseline records, one `size fuel` pair per line.
+fn load_baseline(method: &str) -> BTreeMap<u64, u64> {
+    let path = baseline_path(method);
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|err| {
+        panic!(
+            "{} fuel baseline {:?} not readable ({}), run with {}=1 to record it",
+            method, path, err, BASELINE_UPDATE_VAR
+        )
+    });
+    text.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().map(|field| field.parse().ok());
+            Some((fields.next()??, fields.next()??))
+        })
+        .collect()
+}
+
+fn store_baseline(method: &str, runs: &[(u64, u64)]) {
+    let path = baseline_path(method);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let text: String = runs
+        .iter()
+        .map(|(n, fuel)| format!("{} {}\n", n, fuel))
+        .collect();
+    std::fs::write(&path, text).unwrap();
+    println!("{} fuel baseline recorded in {:?}", method, path);
+}
+
+/// Check the runs against the baseline, or record them if requested.
+fn check_regression(method: &str, runs: &[(u64, u64)]) {
+    if std::env::var(BASELINE_UPDATE_VAR).as_deref() == Ok("1") {
+        store_baseline(method, runs);
+        return;
+    }
+
+    let baseline = load_baseline(method);
+    for (n, fuel) in runs {
+        let expected = *baseline.get(n).unwrap_or_else(|| {
+            panic!(
+                "{}({}) has no fuel baseline record, run with {}=1 to record it",
+                method, n, BASELINE_UPDATE_VAR
+            )
+        });
+        assert!(
+            fuel * 100 <= expected * (100 + REGRESSION_THRESHOLD),
+            "{}({}) fuel regression: {} burned, {} expected",
+            method,
+            n,
+            fuel,
+            expected
+        );
+    }
+}
+
+fn fuel_benchmark(method: &str, step: u64) {
+    let runs = run_benchmark(method, step);
+    check_linear(method, &runs);
+    check_regression(method, &runs);
+}
+
+#[test]
+#[ignore]
+fn fuel_loop() {
+    fuel_benchmark("fuel_loop", 10_000);
+}
+
+#[test]
+#[ignore]
+fn fuel_hash() {
+    fuel_benchmark("fuel_hash", 4096);
+}
+
+#[test]
+#[ignore]
+fn fuel_store() {
+    fuel_benchmark("fuel_store", 10);
+}
+
+#[test]
+#[ignore]
+fn fuel_nested() {
+    fuel_benchmark("fuel_nested", 1);
+}
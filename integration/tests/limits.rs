@@ -0,0 +1,198 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Host resource limits probes
+//!
+//! Every probe executes a test contract method with a growing size looking
+//! for the threshold where the node limits kick in, that is the smallest
+//! failing size. The sizes below the threshold shall succeed and every size
+//! from the threshold on shall fail with the same error. The thresholds and
+//! errors found are printed, run with `--nocapture` to read them.
+//!
+//! The probes execute many transactions, they are ignored by default:
+//! `cargo test --test limits -- --ignored`.
+use integration::{
+    common::{self, *},
+    TestApp,
+};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+use trinci_core::{crypto::Hash, Receipt, Transaction};
+
+const TEST_ALIAS: &str = "Test";
+
+/// Number of sizes executed by each run while narrowing the threshold.
+const BATCH_SIZE: u64 = 16;
+
+lazy_static! {
+    static ref ACCOUNTS_INFO: HashMap<&'static str, AccountInfo> = {
+        let mut map = HashMap::new();
+        map.insert(TEST_ALIAS, AccountInfo::new(PUB_KEY1, PVT_KEY1, ""));
+        map
+    };
+    static ref TEST_APP_HASH: Hash = app_hash("test.wasm").unwrap();
+}
+
+fn probe_tx(test_info: &AccountInfo, method: &str, n: u64) -> Transaction {
+    let args = value!(n);
+    common::create_test_tx(
+        &test_info.id,
+        &test_info.pub_key,
+        &test_info.pvt_key,
+        *TEST_APP_HASH,
+        method,
+        args,
+    )
+}
+
+fn error_message(rx: &Receipt) -> String {
+    String::from_utf8_lossy(&rx.returns).to_string()
+}
+
+/// Execute the probe method with the given sizes on a fresh application.
+fn run_probe(method: &str, sizes: &[u64]) -> Vec<Receipt> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+
+    // 0. Bind the contract to the account, so that the runs are not charged for it.
+    let mut txs = vec![probe_tx(test_info, method, 0)];
+    txs.extend(sizes.iter().map(|n| probe_tx(test_info, method, *n)));
+
+    // Instance the application.
+    let mut app = TestApp::default();
+    let mut rxs = app.exec_txs(txs);
+    assert!(
+        rxs[0].success,
+        "{}(0) failed: {}",
+        method,
+        error_message(&rxs[0])
+    );
+    rxs.split_off(1)
+}
+
+/// Returns the index of the first failing size.
+///
+/// Checks that every size after it fails too.
+fn first_failure(method: &str, sizes: &[u64], rxs: &[Receipt]) -> Option<usize> {
+    let first = rxs.iter().position(|rx| !rx.success)?;
+    for (n, rx) in sizes.iter().zip(rxs.iter()).skip(first) {
+        assert!(
+            !rx.success,
+            "{}({}) succeeded over the threshold",
+            method, n
+        );
+    }
+    Some(first)
+}
+
+/// Look for the smallest failing size up to `max`.
+///
+/// Returns the threshold along with its error, `None` if every size succeeds.
+fn find_threshold(method: &str, max: u64) -> Option<(u64, String)> {
+    // Exponential search of a range containing the threshold.
+    let mut sizes: Vec<u64> = (0..64)
+        .map(|exp| 1u64 << exp)
+        .take_while(|n| *n < max)
+        .collect();
+    sizes.push(max);
+    let rxs = run_probe(method, &sizes);
+    let i = first_failure(method, &sizes, &rxs)?;
+    let mut low = if i == 0 { 0 } else { sizes[i - 1] };
+    let mut high = sizes[i];
+    let mut error = error_message(&rxs[i]);
+
+    // Narrow the range, the lower bound succeeds and the upper one fails.
+    while high - low > 1 {
+        let step = ((high - low) / BATCH_SIZE).max(1);
+        let sizes: Vec<u64> = (low + step..high).step_by(step as usize).collect();
+        let rxs = run_probe(method, &sizes);
+        match first_failure(method, &sizes, &rxs) {
+            Some(i) => {
+                high = sizes[i];
+                error = error_message(&rxs[i]);
+                if i > 0 {
+                    low = sizes[i - 1];
+                }
+            }
+            None => low = *sizes.last().unwrap(),
+        }
+    }
+    Some((high, error))
+}
+
+/// Find the threshold of the probe up to `max` and check that its error is
+/// stable.
+fn probe_limit(method: &str, max: u64) {
+    let (threshold, error) = match find_threshold(method, max) {
+        Some(res) => res,
+        None => {
+            println!("{}: no limit up to {}", method, max);
+            return;
+        }
+    };
+    println!("{}: limit at {}, {}", method, threshold, error);
+    assert!(
+        error.starts_with("smart contract fault: "),
+        "{}({}) unexpected error: {}",
+        method,
+        threshold,
+        error
+    );
+
+    // The threshold is exact and its error does not change between runs.
+    let sizes = [
+        threshold - 1,
+        threshold,
+        threshold,
+        threshold.saturating_mul(2),
+    ];
+    let rxs = run_probe(method, &sizes);
+    let i = first_failure(method, &sizes, &rxs).expect("threshold not reproduced");
+    assert_eq!(i, 1, "{} threshold moved from {}", method, threshold);
+    for rx in rxs[1..].iter() {
+        assert_eq!(error_message(rx), error, "{} error is not stable", method);
+    }
+}
+
+#[test]
+#[ignore]
+fn memory_pages_limit() {
+    // The wasm32 address space is 65536 pages.
+    probe_limit("alloc_pages", 1 << 16);
+}
+
+#[test]
+#[ignore]
+fn recursion_depth_limit() {
+    probe_limit("recurse_to", 1 << 24);
+}
+
+#[test]
+#[ignore]
+fn loop_fuel_limit() {
+    probe_limit("fuel_loop", 1 << 32);
+}
+
+#[test]
+#[ignore]
+fn emitted_events_limit() {
+    probe_limit("emit_events", 1 << 20);
+}
+
+#[test]
+#[ignore]
+fn stored_bytes_limit() {
+    probe_limit("store_bytes", 1 << 26);
+}
@@ -14,26 +14,21 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
-
 //! Pay meals integration tests
 
 use integration::{
-    common::{self, *},
+    clients::{
+        asset::types::InitArgs as AssetInitArgs,
+        pay_meal::{
+            types::InitArgs, BillClosed, SharePayment, CLOSED_EVENT, PAID_EVENT, REFUND_EVENT,
+        },
+        AssetClient, PayMealClient,
+    },
+    common::*,
     TestApp,
 };
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use trinci_core::{base::serialize, crypto::Hash, Receipt, Transaction};
-use trinci_sdk::{rmp_deserialize, tai::Asset};
-
-use serde_value::Value;
-
-lazy_static! {
-    pub static ref PAYMEALS_APP_HASH: Hash = common::app_hash("pay_meal.wasm").unwrap();
-    pub static ref ASSET_APP_HASH: Hash = common::app_hash("asset.wasm").unwrap();
-}
-
-use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 const PAY_ALIAS: &str = "PayMeals";
 const RESTAURATEUR_ALIAS: &str = "Mario's_Pizza";
@@ -59,166 +54,45 @@ lazy_static! {
     };
 }
 
-/// Struct to delegate not-owner account to perform payment
-#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
-pub struct Delegation<'a> {
-    /// The delegate account (will be the transfer caller)
-    pub delegate: &'a str,
-    /// Amount of asset to allow the transfer
-    pub units: u64,
-    /// Destination account for the transfer
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub to: Option<&'a str>,
+fn asset_init_args() -> AssetInitArgs<'static> {
+    AssetInitArgs {
+        name: ASSET_ALIAS,
+        authorized: vec![],
+        description: "My Cool Coin",
+        url: "https://fck.you",
+        max_units: 100_000,
+    }
 }
 
-fn contract_init_tx(
-    contract: &AccountInfo,
-    restaurateur: &AccountInfo,
-    asset: &AccountInfo,
-    customer1: &AccountInfo,
-    customer2: &AccountInfo,
-    customer3: &AccountInfo,
+fn contract_init_args<'a>(
+    restaurateur: &'a AccountInfo,
+    asset: &'a AccountInfo,
+    customers: [&'a AccountInfo; 3],
     part: u64,
-) -> Transaction {
-    // Initialization data
-    let args = value!({
-        "restaurateur": restaurateur.id,
-        "asset": asset.id,
-        "part": part,
-        "customers": {
-            customer1.id.clone(): false,
-            customer2.id.clone(): false,
-            customer3.id.clone(): false,
-        },
-        "status": "open",
-    });
-
-    common::create_test_tx(
-        &contract.id,
-        &contract.pub_key,
-        &contract.pvt_key,
-        *PAYMEALS_APP_HASH,
-        "init",
-        args,
-    )
+) -> InitArgs<'a> {
+    InitArgs {
+        restaurateur: &restaurateur.id,
+        asset: &asset.id,
+        part,
+        customers: customers
+            .iter()
+            .map(|customer| (customer.id.as_str(), false))
+            .collect(),
+        status: "open",
+        payers: BTreeMap::new(),
+        collected: BTreeSet::new(),
+    }
 }
 
-fn contract_get_info_tx(contract: &AccountInfo, caller: &AccountInfo) -> Transaction {
-    let args = value!(null);
-
-    common::create_test_tx(
-        &contract.id,
-        &caller.pub_key,
-        &caller.pvt_key,
-        *PAYMEALS_APP_HASH,
-        "get_info",
-        args,
-    )
+fn fault(msg: &str) -> Result<(), String> {
+    Err(format!("smart contract fault: {}", msg))
 }
 
-fn contract_apply_tx(contract: &AccountInfo, customer: &AccountInfo) -> Transaction {
-    let args = value!(null);
-
-    common::create_test_tx(
-        &contract.id,
-        &customer.pub_key,
-        &customer.pvt_key,
-        *PAYMEALS_APP_HASH,
-        "apply",
-        args,
-    )
-}
-
-fn contract_close_tx(contract: &AccountInfo, caller: &AccountInfo) -> Transaction {
-    let args = value!(null);
-
-    common::create_test_tx(
-        &contract.id,
-        &caller.pub_key,
-        &caller.pvt_key,
-        *PAYMEALS_APP_HASH,
-        "close",
-        args,
-    )
-}
-
-pub fn asset_init_tx(asset_info: &AccountInfo, asset_name: &str) -> Transaction {
-    let args = value!({
-        "name": asset_name,
-        "description": "My Cool Coin",
-        "url": "https://fck.you",
-        "max_units": 100_000,
-        "authorized": [],
-    });
-    create_test_tx(
-        &asset_info.id,
-        &asset_info.pub_key,
-        &asset_info.pvt_key,
-        *ASSET_APP_HASH,
-        "init",
-        args,
-    )
-}
-
-pub fn asset_mint_tx(asset_info: &AccountInfo, to_info: &AccountInfo, units: u64) -> Transaction {
-    let args = value!({
-        "to": to_info.id,
-        "units": units,
-    });
-    create_test_tx(
-        &asset_info.id,
-        &asset_info.pub_key,
-        &asset_info.pvt_key,
-        *ASSET_APP_HASH,
-        "mint",
-        args,
-    )
-}
-
-pub fn asset_transfer_tx(
-    asset_info: &AccountInfo,
-    from_info: &AccountInfo,
-    to_info: &AccountInfo,
-    units: u64,
-) -> Transaction {
-    let args = value!({
-        "from": from_info.id,
-        "to": to_info.id,
-        "units": units,
-    });
-    create_test_tx(
-        &asset_info.id,
-        &from_info.pub_key,
-        &from_info.pvt_key,
-        *ASSET_APP_HASH,
-        "transfer",
-        args,
-    )
-}
-
-fn asset_add_delegation_tx(
-    asset_info: &AccountInfo,
-    delegator_info: &AccountInfo,
-    delegate_info: &AccountInfo,
-    units: u64,
-) -> Transaction {
-    let args = Delegation {
-        delegate: &delegate_info.id,
-        units,
-        to: Some(&delegate_info.id),
-    };
-
-    common::create_test_tx(
-        &asset_info.id,
-        &delegator_info.pub_key,
-        &delegator_info.pvt_key,
-        *ASSET_APP_HASH,
-        "add_delegation",
-        args,
-    )
-}
+#[test]
+fn pay_meal_test() {
+    // Instance the application.
+    let mut app = TestApp::default();
 
-fn create_txs() -> Vec<Transaction> {
     let contract_info = ACCOUNTS_INFO.get(PAY_ALIAS).unwrap();
     let restaurateur_info = ACCOUNTS_INFO.get(RESTAURATEUR_ALIAS).unwrap();
     let marco_info = ACCOUNTS_INFO.get(MARCO_ALIAS).unwrap();
@@ -227,163 +101,182 @@ fn create_txs() -> Vec<Transaction> {
     let piero_info = ACCOUNTS_INFO.get(PIERO_ALIAS).unwrap();
     let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
 
-    vec![
-        // 0. Initialize src asset
-        asset_init_tx(asset_info, ASSET_ALIAS),
-        // 1. Mint some units in customers account.
-        asset_mint_tx(asset_info, marco_info, 100),
-        // 2. Mint some units in customers account.
-        asset_mint_tx(asset_info, luigi_info, 100),
-        // 3. Mint some units in customers account.
-        asset_mint_tx(asset_info, bruno_info, 100),
-        // 4. Mint some units in customers account.
-        asset_mint_tx(asset_info, piero_info, 100),
-        // 5. Initialize contract account.
-        contract_init_tx(
-            contract_info,
-            restaurateur_info,
-            asset_info,
-            marco_info,
-            luigi_info,
-            bruno_info,
-            30,
-        ),
-        // 6. Marco get the contract info
-        contract_get_info_tx(contract_info, marco_info),
-        // 7. Luigi add delegation to pay the bill
-        asset_add_delegation_tx(asset_info, luigi_info, contract_info, 30),
-        // 8. Luigi pays his bill
-        contract_apply_tx(contract_info, luigi_info),
-        // 9. Piero tries to pay. This shall fail.
-        contract_apply_tx(contract_info, piero_info),
-        // 10. Piero tries to get contract information. This shall fail.
-        contract_get_info_tx(contract_info, piero_info),
-        // 11. Marco tries to close the contract. This shall fail.
-        contract_close_tx(contract_info, marco_info),
-        // 12. Mario (the restaurateur) tries to close the contract.
-        contract_close_tx(contract_info, restaurateur_info),
-        // 13. Bruno add delegation to pay the bill
-        asset_add_delegation_tx(asset_info, bruno_info, contract_info, 30),
-        // 14. Bruno pays his bill
-        contract_apply_tx(contract_info, bruno_info),
-        // 15. Marco add delegation to pay the bill
-        asset_add_delegation_tx(asset_info, marco_info, contract_info, 30),
-        // 16. Marco pays his bill
-        contract_apply_tx(contract_info, marco_info),
-        // 17. Mario (the restaurateur) tries to close the contract.
-        contract_close_tx(contract_info, restaurateur_info),
-        // 18. Mario get the contract information
-        contract_get_info_tx(contract_info, restaurateur_info),
-    ]
-}
-
-fn check_rxs(rxs: Vec<Receipt>) {
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
     // 0. Initialize src asset
-    assert!(rxs[0].success);
+    asset.init(asset_info, asset_init_args()).unwrap();
     // 1. Mint some units in customers account.
-    assert!(rxs[1].success);
+    asset.mint(asset_info, &marco_info.id, 100).unwrap();
     // 2. Mint some units in customers account.
-    assert!(rxs[2].success);
+    asset.mint(asset_info, &luigi_info.id, 100).unwrap();
     // 3. Mint some units in customers account.
-    assert!(rxs[3].success);
+    asset.mint(asset_info, &bruno_info.id, 100).unwrap();
     // 4. Mint some units in customers account.
-    assert!(rxs[4].success);
+    asset.mint(asset_info, &piero_info.id, 100).unwrap();
+
+    let mut pay = PayMealClient::new(&mut app, &contract_info.id);
     // 5. Initialize contract account.
-    assert!(rxs[5].success);
+    let args = contract_init_args(
+        restaurateur_info,
+        asset_info,
+        [marco_info, luigi_info, bruno_info],
+        30,
+    );
+    pay.init(contract_info, args).unwrap();
     // 6. Marco get the contract info
-    assert!(rxs[6].success);
-    // Checks on the config
-    let config: Value = rmp_deserialize(&rxs[6].returns).unwrap();
-    let status = config.get(&value!("status")).unwrap().as_str().unwrap();
-    assert_eq!(status, "open");
+    let info = pay.customer_info(marco_info).unwrap();
+    // Checks on the customer view
+    assert_eq!(info.customer, marco_info.id);
+    assert!(!info.paid);
+    assert_eq!(info.summary.status, "open");
+
     // 7. Luigi add delegation to pay the bill
-    assert!(rxs[7].success);
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(luigi_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
+
+    let mut pay = PayMealClient::new(&mut app, &contract_info.id);
     // 8. Luigi pays his bill
-    assert!(rxs[8].success);
+    pay.apply(luigi_info).unwrap();
+    // Checks on the payment event
+    let event: SharePayment = pay.event(0, PAID_EVENT).unwrap();
+    assert_eq!(event.customer, luigi_info.id);
+    assert_eq!(event.payer, luigi_info.id);
+    assert_eq!(event.units, 30);
     // 9. Piero tries to pay. This shall fail.
-    assert!(!rxs[9].success);
-    assert_eq!(
-        String::from_utf8_lossy(&rxs[9].returns),
-        "smart contract fault: not authorized"
-    );
+    assert_eq!(pay.apply(piero_info), fault("not authorized"));
     // 10. Piero tries to get contract information. This shall fail.
-    assert!(!rxs[10].success);
     assert_eq!(
-        String::from_utf8_lossy(&rxs[9].returns),
-        "smart contract fault: not authorized"
+        pay.customer_info(piero_info).map(|_| ()),
+        fault("not authorized")
     );
     // 11. Marco tries to close the contract. This shall fail.
-    assert!(!rxs[11].success);
-    assert_eq!(
-        String::from_utf8_lossy(&rxs[9].returns),
-        "smart contract fault: not authorized"
-    );
+    assert_eq!(pay.close(marco_info), fault("not authorized"));
     // 12. Mario (the restaurateur) tries to close the contract.
-    assert!(rxs[12].success);
+    pay.close(restaurateur_info).unwrap();
+    // Checks on the close event
+    let event: BillClosed = pay.event(0, CLOSED_EVENT).unwrap();
+    assert_eq!(event.amount, 30);
+    assert_eq!(event.status, "open");
+
     // 13. Bruno add delegation to pay the bill
-    assert!(rxs[13].success);
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(bruno_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
     // 14. Bruno pays his bill
-    assert!(rxs[14].success);
+    PayMealClient::new(&mut app, &contract_info.id)
+        .apply(bruno_info)
+        .unwrap();
     // 15. Marco add delegation to pay the bill
-    assert!(rxs[15].success);
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(marco_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
+
+    let mut pay = PayMealClient::new(&mut app, &contract_info.id);
     // 16. Marco pays his bill
-    assert!(rxs[16].success);
+    pay.apply(marco_info).unwrap();
     // 17. Mario (the restaurateur) tries to close the contract.
-    assert!(rxs[17].success);
+    pay.close(restaurateur_info).unwrap();
+    // Checks on the close event
+    let event: BillClosed = pay.event(0, CLOSED_EVENT).unwrap();
+    assert_eq!(event.amount, 60);
+    assert_eq!(event.status, "close");
     // 18. Mario get the contract information
-    assert!(rxs[18].success);
-    // Checks on the config
-    let config: Value = rmp_deserialize(&rxs[18].returns).unwrap();
-    let status = config.get(&value!("status")).unwrap().as_str().unwrap();
-    assert_eq!(status, "close");
+    let config = pay.config(restaurateur_info).unwrap();
+    assert_eq!(config.status, "close");
+    // 19. Piero get the contract public summary
+    let summary = pay.public_summary(piero_info).unwrap();
+    assert_eq!(summary.paid, 3);
+    assert_eq!(summary.total, 90);
+
+    // Blockchain check.
+
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+
+    assert_eq!(asset.asset(&contract_info.id).unwrap().units, 0);
+    assert_eq!(asset.asset(&restaurateur_info.id).unwrap().units, 90);
+    assert_eq!(asset.asset(&bruno_info.id).unwrap().units, 70);
+    assert_eq!(asset.asset(&marco_info.id).unwrap().units, 70);
+    assert_eq!(asset.asset(&luigi_info.id).unwrap().units, 70);
+    assert_eq!(asset.asset(&piero_info.id).unwrap().units, 100);
 }
 
 #[test]
-fn pay_meal_test() {
+fn pay_meal_delegated_payment_test() {
     // Instance the application.
     let mut app = TestApp::default();
 
-    // Create and execute transactions.
-    let txs = create_txs();
-    let rxs = app.exec_txs(txs);
-    check_rxs(rxs);
-
-    // Blockchain check.
-    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
     let contract_info = ACCOUNTS_INFO.get(PAY_ALIAS).unwrap();
     let restaurateur_info = ACCOUNTS_INFO.get(RESTAURATEUR_ALIAS).unwrap();
     let marco_info = ACCOUNTS_INFO.get(MARCO_ALIAS).unwrap();
     let luigi_info = ACCOUNTS_INFO.get(LUIGI_ALIAS).unwrap();
     let bruno_info = ACCOUNTS_INFO.get(BRUNO_ALIAS).unwrap();
     let piero_info = ACCOUNTS_INFO.get(PIERO_ALIAS).unwrap();
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
 
-    let contract_account = app.account(&contract_info.id).unwrap();
-    let contract_asset: Asset =
-        serialize::rmp_deserialize(&contract_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(contract_asset.units, 0);
-
-    let restaurateur_account = app.account(&restaurateur_info.id).unwrap();
-    let restaurateur_asset: Asset =
-        serialize::rmp_deserialize(&restaurateur_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(restaurateur_asset.units, 90);
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Initialize src asset
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some units in Luigi account.
+    asset.mint(asset_info, &luigi_info.id, 100).unwrap();
+    // 2. Mint some units in Piero account.
+    asset.mint(asset_info, &piero_info.id, 100).unwrap();
+
+    // 3. Initialize contract account.
+    let args = contract_init_args(
+        restaurateur_info,
+        asset_info,
+        [marco_info, luigi_info, bruno_info],
+        30,
+    );
+    PayMealClient::new(&mut app, &contract_info.id)
+        .init(contract_info, args)
+        .unwrap();
+    // 4. Piero add delegation to pay the Marco bill
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(piero_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
+
+    let mut pay = PayMealClient::new(&mut app, &contract_info.id);
+    // 5. Piero pays the Marco bill
+    pay.pay_for(piero_info, &marco_info.id).unwrap();
+    // 6. Piero tries to pay again the Marco bill. This shall fail.
+    assert_eq!(
+        pay.pay_for(piero_info, &marco_info.id),
+        fault("already paid")
+    );
 
-    let bruno_account = app.account(&bruno_info.id).unwrap();
-    let bruno_asset: Asset =
-        serialize::rmp_deserialize(&bruno_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(bruno_asset.units, 70);
+    // 7. Luigi add delegation to pay the bill
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(luigi_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
+
+    let mut pay = PayMealClient::new(&mut app, &contract_info.id);
+    // 8. Luigi pays the bill
+    pay.apply(luigi_info).unwrap();
+    // 9. Marco tries to cancel the contract. This shall fail.
+    assert_eq!(pay.cancel(marco_info), fault("not authorized"));
+    // 10. Mario (the restaurateur) cancels the contract.
+    pay.cancel(restaurateur_info).unwrap();
+    // Checks on the refund events, one for each paid share
+    let mut refunds = HashMap::new();
+    for index in 0..2 {
+        let event: SharePayment = pay.event(index, REFUND_EVENT).unwrap();
+        assert_eq!(event.units, 30);
+        refunds.insert(event.customer, event.payer);
+    }
+    assert_eq!(refunds.len(), 2);
+    assert_eq!(refunds[&marco_info.id], piero_info.id);
+    assert_eq!(refunds[&luigi_info.id], luigi_info.id);
+    // 11. Mario get the contract information
+    let config = pay.config(restaurateur_info).unwrap();
+    assert_eq!(config.status, "cancelled");
 
-    let marco_account = app.account(&marco_info.id).unwrap();
-    let marco_asset: Asset =
-        serialize::rmp_deserialize(&marco_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(marco_asset.units, 70);
+    // Blockchain check.
 
-    let luigi_account = app.account(&luigi_info.id).unwrap();
-    let luigi_asset: Asset =
-        serialize::rmp_deserialize(&luigi_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(luigi_asset.units, 70);
+    let asset = AssetClient::new(&mut app, &asset_info.id);
 
-    let piero_account = app.account(&piero_info.id).unwrap();
-    let piero_asset: Asset =
-        serialize::rmp_deserialize(&piero_account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(piero_asset.units, 100);
+    assert_eq!(asset.asset(&contract_info.id).unwrap().units, 0);
+    // The refunds go back to who actually paid
+    assert_eq!(asset.asset(&luigi_info.id).unwrap().units, 100);
+    assert_eq!(asset.asset(&piero_info.id).unwrap().units, 100);
 }
@@ -0,0 +1,204 @@
+// This file is part of TRINCI.
+//
+// Copyright (C) 2021 Affidaty Spa.
+//
+// TRINCI is free software: you can redistribute it and/or modify it under
+// the terms of the GNU Affero General Public License as published by the
+// Free Software Foundation, either version 3 of the License, or (at your
+// option) any later version.
+//
+// TRINCI is distributed in the hope that it will be useful, but WITHOUT
+// ANY WARRANTY; without even the implied warranty of MERCHANTABILITY or
+// FITNESS FOR A PARTICULAR PURPOSE. See the GNU Affero General Public License
+// for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
+//! Split payment integration tests
+
+use integration::{
+    clients::{
+        asset::types::InitArgs, split_payment::types::OpenArgs, AssetClient, SplitPaymentClient,
+    },
+    common::*,
+    TestApp,
+};
+use lazy_static::lazy_static;
+use std::collections::HashMap;
+
+const SPLIT_ALIAS: &str = "SplitPayment";
+const MERCHANT_ALIAS: &str = "Mario's_Pizza";
+const MARCO_ALIAS: &str = "Marco";
+const LUIGI_ALIAS: &str = "Luigi";
+const BRUNO_ALIAS: &str = "Bruno";
+const PIERO_ALIAS: &str = "Piero";
+
+const ASSET_ALIAS: &str = "Asset";
+
+const LUNCH_ID: &str = "lunch";
+const DINNER_ID: &str = "dinner";
+
+lazy_static! {
+    static ref ACCOUNTS_INFO: HashMap<&'static str, AccountInfo> = {
+        let mut map = HashMap::new();
+        map.insert(SPLIT_ALIAS, AccountInfo::new(PUB_KEY1, PVT_KEY1, ""));
+        map.insert(MERCHANT_ALIAS, AccountInfo::new(PUB_KEY2, PVT_KEY2, ""));
+        map.insert(MARCO_ALIAS, AccountInfo::new(PUB_KEY3, PVT_KEY3, ""));
+        map.insert(LUIGI_ALIAS, AccountInfo::new(PUB_KEY4, PVT_KEY4, ""));
+        map.insert(BRUNO_ALIAS, AccountInfo::new(PUB_KEY5, PVT_KEY5, ""));
+        map.insert(PIERO_ALIAS, AccountInfo::new(PUB_KEY6, PVT_KEY6, ""));
+        map.insert(ASSET_ALIAS, AccountInfo::new(PUB_KEY7, PVT_KEY7, ""));
+        map
+    };
+}
+
+fn asset_init_args() -> InitArgs<'static> {
+    InitArgs {
+        name: ASSET_ALIAS,
+        authorized: vec![],
+        description: "My Cool Coin",
+        url: "https://fck.you",
+        max_units: 100_000,
+    }
+}
+
+fn open_args<'a>(
+    id: &'a str,
+    merchant: &'a AccountInfo,
+    asset: &'a AccountInfo,
+    participants: &[&'a AccountInfo],
+    part: u64,
+) -> OpenArgs<'a> {
+    OpenArgs {
+        id,
+        merchant: &merchant.id,
+        asset: &asset.id,
+        part,
+        participants: participants.iter().map(|p| p.id.as_str()).collect(),
+    }
+}
+
+fn fault(msg: &str) -> Result<(), String> {
+    Err(format!("smart contract fault: {}", msg))
+}
+
+#[test]
+fn split_payment_test() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let contract_info = ACCOUNTS_INFO.get(SPLIT_ALIAS).unwrap();
+    let merchant_info = ACCOUNTS_INFO.get(MERCHANT_ALIAS).unwrap();
+    let marco_info = ACCOUNTS_INFO.get(MARCO_ALIAS).unwrap();
+    let luigi_info = ACCOUNTS_INFO.get(LUIGI_ALIAS).unwrap();
+    let bruno_info = ACCOUNTS_INFO.get(BRUNO_ALIAS).unwrap();
+    let piero_info = ACCOUNTS_INFO.get(PIERO_ALIAS).unwrap();
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Initialize src asset
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some units in participants account.
+    asset.mint(asset_info, &marco_info.id, 100).unwrap();
+    // 2. Mint some units in participants account.
+    asset.mint(asset_info, &luigi_info.id, 100).unwrap();
+    // 3. Mint some units in participants account.
+    asset.mint(asset_info, &bruno_info.id, 100).unwrap();
+
+    let mut split = SplitPaymentClient::new(&mut app, &contract_info.id);
+    // 4. Open the lunch bill for Marco and Luigi.
+    let args = open_args(
+        LUNCH_ID,
+        merchant_info,
+        asset_info,
+        &[marco_info, luigi_info],
+        20,
+    );
+    split.open(contract_info, args).unwrap();
+    // 5. Open the dinner bill for Luigi and Bruno.
+    let args = open_args(
+        DINNER_ID,
+        merchant_info,
+        asset_info,
+        &[luigi_info, bruno_info],
+        30,
+    );
+    split.open(contract_info, args).unwrap();
+    // 6. Open again the lunch bill. This shall fail.
+    let args = open_args(LUNCH_ID, merchant_info, asset_info, &[marco_info], 10);
+    assert_eq!(
+        split.open(contract_info, args),
+        fault("bill already exists")
+    );
+    // 7. Marco tries to open a bill. This shall fail.
+    let args = open_args("breakfast", marco_info, asset_info, &[luigi_info], 10);
+    assert_eq!(split.open(marco_info, args), fault("not authorized"));
+
+    // 8. Marco add delegation to pay the lunch bill
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(marco_info, &contract_info.id, 20, Some(&contract_info.id))
+        .unwrap();
+
+    let mut split = SplitPaymentClient::new(&mut app, &contract_info.id);
+    // 9. Marco pays the lunch bill
+    split.apply(marco_info, LUNCH_ID).unwrap();
+    // 10. Bruno tries to pay the lunch bill. This shall fail.
+    assert_eq!(split.apply(bruno_info, LUNCH_ID), fault("not authorized"));
+
+    // 11. Luigi add delegation to pay the lunch bill
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(luigi_info, &contract_info.id, 20, Some(&contract_info.id))
+        .unwrap();
+
+    let mut split = SplitPaymentClient::new(&mut app, &contract_info.id);
+    // 12. Luigi pays the lunch bill
+    split.apply(luigi_info, LUNCH_ID).unwrap();
+    // 13. Luigi tries to pay again the lunch bill. This shall fail.
+    assert_eq!(split.apply(luigi_info, LUNCH_ID), fault("already paid"));
+    // 14. Piero tries to get the dinner bill information. This shall fail.
+    assert_eq!(
+        split.get_info(piero_info, DINNER_ID).map(|_| ()),
+        fault("not authorized")
+    );
+    // 15. Mario (the merchant) closes the lunch bill.
+    split.close(merchant_info, LUNCH_ID).unwrap();
+    // 16. Marco tries to close the dinner bill. This shall fail.
+    assert_eq!(split.close(marco_info, DINNER_ID), fault("not authorized"));
+
+    // 17. Marco add delegation to pay for Bruno
+    AssetClient::new(&mut app, &asset_info.id)
+        .add_delegation(marco_info, &contract_info.id, 30, Some(&contract_info.id))
+        .unwrap();
+
+    let mut split = SplitPaymentClient::new(&mut app, &contract_info.id);
+    // 18. Marco pays the Bruno share of the dinner bill
+    split
+        .pay_for(marco_info, DINNER_ID, &bruno_info.id)
+        .unwrap();
+    // 19. Mario (the merchant) cancels the dinner bill.
+    split.cancel(merchant_info, DINNER_ID).unwrap();
+    // 20. Mario get the lunch bill information
+    let bill = split.get_info(merchant_info, LUNCH_ID).unwrap();
+    assert_eq!(bill.status, "close");
+    // 21. Mario get the dinner bill information
+    let bill = split.get_info(merchant_info, DINNER_ID).unwrap();
+    assert_eq!(bill.status, "cancelled");
+    // 22. Mario tries to get an unknown bill information. This shall fail.
+    assert_eq!(
+        split.get_info(merchant_info, "breakfast").map(|_| ()),
+        fault("bill not found")
+    );
+
+    // Blockchain check.
+
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+
+    // Nothing is left in escrow
+    assert_eq!(asset.asset(&contract_info.id).unwrap().units, 0);
+    // The merchant collected only the lunch bill
+    assert_eq!(asset.asset(&merchant_info.id).unwrap().units, 40);
+    // The dinner shares went back to who actually paid
+    assert_eq!(asset.asset(&marco_info.id).unwrap().units, 80);
+    assert_eq!(asset.asset(&luigi_info.id).unwrap().units, 80);
+    assert_eq!(asset.asset(&bruno_info.id).unwrap().units, 100);
+}
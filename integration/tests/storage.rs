@@ -14,26 +14,30 @@
 //
 // You should have received a copy of the GNU Affero General Public License
 // along with TRINCI. If not, see <https://www.gnu.org/licenses/>.
-
 //! Storage integration test
 
 use integration::{
+    clients::{
+        asset::types::InitArgs,
+        storage::{
+            types::{
+                AddOrderArgs, BatchArgs, BatchOp, Envelope, Permissions, Precondition, Quota,
+                SetOperatorArgs, SpendingLimit, TransferArgs,
+            },
+            QuotaLimits, QuotaReport, QuotaUsage, StoredBlob, TransferOutcome,
+        },
+        AssetClient, StorageClient,
+    },
     common::{
-        self, AccountInfo, PUB_KEY1, PUB_KEY2, PUB_KEY3, PUB_KEY4, PVT_KEY1, PVT_KEY2, PVT_KEY3,
-        PVT_KEY4,
+        AccountInfo, PUB_KEY1, PUB_KEY2, PUB_KEY3, PUB_KEY4, PVT_KEY1, PVT_KEY2, PVT_KEY3, PVT_KEY4,
     },
     TestApp,
 };
 use lazy_static::lazy_static;
-use std::collections::HashMap;
-use trinci_core::crypto::Hash;
-use trinci_core::{base::serialize, Receipt, Transaction};
-use trinci_sdk::{tai::Asset, value};
+use std::collections::{BTreeMap, HashMap};
+use trinci_sdk::{rmp_deserialize, value, Value};
 
-lazy_static! {
-    static ref STORAGE_APP_HASH: Hash = common::app_hash("storage.wasm").unwrap();
-    static ref ASSET_APP_HASH: Hash = common::app_hash("asset.wasm").unwrap();
-}
+use serde_bytes::ByteBuf;
 
 const ASSET_ALIAS: &str = "FCK";
 const ALICE_ALIAS: &str = "Alice";
@@ -51,225 +55,784 @@ lazy_static! {
     };
 }
 
-fn asset_init_tx(asset_info: &AccountInfo) -> Transaction {
-    let args = value!({
-        "name": ASSET_ALIAS,
-        "authorized": Vec::<&str>::new(),
-        "description": "My Cool Coin",
-        "url": "https://fck.you",
-        "max_units": 100_000,
-    });
-    common::create_test_tx(
-        &asset_info.id,
-        &asset_info.pub_key,
-        &asset_info.pvt_key,
-        *ASSET_APP_HASH,
-        "init",
-        args,
-    )
+fn asset_init_args() -> InitArgs<'static> {
+    InitArgs {
+        name: ASSET_ALIAS,
+        authorized: vec![],
+        description: "My Cool Coin",
+        url: "https://fck.you",
+        max_units: 100_000,
+    }
 }
 
-fn asset_mint_tx(asset_info: &AccountInfo, to_info: &AccountInfo, units: u64) -> Transaction {
-    let args = value!({
-        "to": to_info.id,
-        "units": units,
-    });
-    common::create_test_tx(
-        &asset_info.id,
-        &asset_info.pub_key,
-        &asset_info.pvt_key,
-        *ASSET_APP_HASH,
-        "mint",
-        args,
-    )
+fn fault(msg: &str) -> Result<(), String> {
+    Err(format!("smart contract fault: {}", msg))
 }
 
-fn transfer_tx(
-    asset_info: &AccountInfo,
-    from_info: &AccountInfo,
-    to_info: &AccountInfo,
-    units: u64,
-) -> Transaction {
-    let args = value!({
-        "to": to_info.id,
-        "asset": asset_info.id,
-        "units": units,
-    });
-    common::create_test_tx(
-        &from_info.id,
-        &from_info.pub_key,
-        &from_info.pvt_key,
-        *STORAGE_APP_HASH,
-        "transfer",
-        args,
-    )
-}
+#[test]
+fn simple_transfers() {
+    // Instance the application.
+    let mut app = TestApp::default();
 
-fn create_payments_txs() -> Vec<Transaction> {
     let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
     let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
     let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
     let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
-    vec![
-        // 0. Asset initialization.
-        asset_init_tx(asset_info),
-        // 1. Mint some funds in Alice's account.
-        asset_mint_tx(asset_info, alice_info, 100),
-        // 2. Transfer from Alice to Bob.
-        transfer_tx(asset_info, alice_info, bob_info, 10),
-        // 3. Transfer from Bob to Dave.
-        transfer_tx(asset_info, bob_info, dave_info, 3),
-        // 4. Transfer from Dave to Alice.
-        transfer_tx(asset_info, dave_info, alice_info, 1),
-        // 5. Transfer from Alice to Bob. Shall fail for insufficient funds.
-        transfer_tx(asset_info, alice_info, bob_info, 100),
-    ]
+
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Asset initialization.
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some funds in Alice's account.
+    asset.mint(asset_info, &alice_info.id, 100).unwrap();
+    // 2. Transfer from Alice to Bob.
+    StorageClient::new(&mut app, &alice_info.id)
+        .transfer(alice_info, &bob_info.id, &asset_info.id, 10)
+        .unwrap();
+    // 3. Transfer from Bob to Dave.
+    StorageClient::new(&mut app, &bob_info.id)
+        .transfer(bob_info, &dave_info.id, &asset_info.id, 3)
+        .unwrap();
+    // 4. Transfer from Dave to Alice.
+    StorageClient::new(&mut app, &dave_info.id)
+        .transfer(dave_info, &alice_info.id, &asset_info.id, 1)
+        .unwrap();
+    // 5. Transfer from Alice to Bob. Shall fail for insufficient funds.
+    assert_eq!(
+        StorageClient::new(&mut app, &alice_info.id).transfer(
+            alice_info,
+            &bob_info.id,
+            &asset_info.id,
+            100
+        ),
+        fault("smart contract fault: insufficient funds")
+    );
+
+    // Blockchain check.
+
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+    assert_eq!(asset.asset(&alice_info.id).unwrap().units, 91);
+    assert_eq!(asset.asset(&bob_info.id).unwrap().units, 7);
+    assert_eq!(asset.asset(&dave_info.id).unwrap().units, 2);
 }
 
-fn check_payment_rxs(rxs: Vec<Receipt>) {
-    // 0.
-    assert!(rxs[0].success);
-    // 1.
-    assert!(rxs[1].success);
-    // 2.
-    assert!(rxs[2].success);
-    // 3.
-    assert!(rxs[3].success);
-    // 4.
-    assert!(rxs[4].success);
-    // 5.
-    assert!(!rxs[5].success);
-    let msg = String::from_utf8_lossy(&rxs[5].returns);
-    assert_eq!(
-        msg,
-        "smart contract fault: smart contract fault: insufficient funds"
+#[test]
+fn multi_asset_transfers() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Asset initialization.
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some funds in Alice's account.
+    asset.mint(asset_info, &alice_info.id, 100).unwrap();
+
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // 2. Transfer from Alice to Bob and Dave.
+    let transfers = vec![
+        TransferArgs {
+            to: &bob_info.id,
+            asset: &asset_info.id,
+            units: 10,
+        },
+        TransferArgs {
+            to: &dave_info.id,
+            asset: &asset_info.id,
+            units: 20,
+        },
+    ];
+    let results = storage.multi_transfer(alice_info, transfers).unwrap();
+    assert_eq!(
+        results[1],
+        TransferOutcome {
+            to: dave_info.id.clone(),
+            asset: asset_info.id.clone(),
+            units: 20,
+            balance: 70,
+        }
     );
+    // 3. Transfer from Alice to Bob and Dave. Shall fail for insufficient funds.
+    let transfers = vec![
+        TransferArgs {
+            to: &bob_info.id,
+            asset: &asset_info.id,
+            units: 10,
+        },
+        TransferArgs {
+            to: &dave_info.id,
+            asset: &asset_info.id,
+            units: 100,
+        },
+    ];
+    assert_eq!(
+        storage.multi_transfer(alice_info, transfers).map(|_| ()),
+        fault("transfer 1 failed: smart contract fault: insufficient funds")
+    );
+    // 4. Get Alice balances.
+    let balances = storage.balances(alice_info, vec![&asset_info.id]).unwrap();
+    assert_eq!(balances, vec![70]);
+
+    // Blockchain check: the failed multi transfer left no partial transfer.
+
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+    assert_eq!(asset.asset(&bob_info.id).unwrap().units, 10);
+}
+
+fn order_args<'a>(
+    id: &'a str,
+    to_info: &'a AccountInfo,
+    asset_info: &'a AccountInfo,
+    units: u64,
+    reward: u64,
+    interval: u64,
+) -> AddOrderArgs<'a> {
+    AddOrderArgs {
+        id,
+        to: &to_info.id,
+        asset: &asset_info.id,
+        units,
+        reward,
+        start: 0,
+        interval,
+    }
 }
 
 #[test]
-fn simple_transfers() {
+fn standing_orders() {
     // Instance the application.
     let mut app = TestApp::default();
 
-    // Create and execute transactions.
-    let txs = create_payments_txs();
-    let rxs = app.exec_txs(txs);
-    check_payment_rxs(rxs);
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Asset initialization.
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some funds in Alice's account.
+    asset.mint(asset_info, &alice_info.id, 100).unwrap();
+
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // 2. Alice registers a periodic order to pay Dave.
+    let args = order_args("rent", dave_info, asset_info, 10, 1, 1_000_000_000);
+    storage.add_order(alice_info, args).unwrap();
+    // 3. Alice registers a single order to pay Dave.
+    let args = order_args("gift", dave_info, asset_info, 5, 1, 0);
+    storage.add_order(alice_info, args).unwrap();
+    // 4. Bob executes the due orders of Alice.
+    let executed = storage.execute_due(bob_info).unwrap();
+    assert_eq!(executed, vec!["gift", "rent"]);
+    // 5. Bob executes again the due orders of Alice.
+    let executed = storage.execute_due(bob_info).unwrap();
+    assert!(executed.is_empty());
 
     // Blockchain check.
 
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+    assert_eq!(asset.asset(&alice_info.id).unwrap().units, 83);
+    assert_eq!(asset.asset(&bob_info.id).unwrap().units, 2);
+    assert_eq!(asset.asset(&dave_info.id).unwrap().units, 15);
+}
+
+#[test]
+fn standing_orders_unpayable_reward() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
     let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
     let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
     let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
     let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
 
-    let account = app.account(&alice_info.id).unwrap();
-    let asset: Asset = serialize::rmp_deserialize(&account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(asset.units, 91);
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Asset initialization.
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some funds in Alice's account.
+    asset.mint(asset_info, &alice_info.id, 20).unwrap();
+
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // 2. Alice registers an order whose reward exceeds the remaining funds.
+    let args = order_args("bonus", dave_info, asset_info, 15, 10, 0);
+    storage.add_order(alice_info, args).unwrap();
+    // 3. Alice registers an order that can be paid.
+    let args = order_args("rent", dave_info, asset_info, 5, 1, 0);
+    storage.add_order(alice_info, args).unwrap();
+    // 4. Bob executes the due orders of Alice.
+    let executed = storage.execute_due(bob_info).unwrap();
+    assert_eq!(executed, vec!["rent"]);
 
-    let account = app.account(&bob_info.id).unwrap();
-    let asset: Asset = serialize::rmp_deserialize(&account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(asset.units, 7);
+    // Blockchain check: the unpayable order moved no funds.
 
-    let account = app.account(&dave_info.id).unwrap();
-    let asset: Asset = serialize::rmp_deserialize(&account.load_asset(&asset_info.id)).unwrap();
-    assert_eq!(asset.units, 2);
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+    assert_eq!(asset.asset(&alice_info.id).unwrap().units, 14);
+    assert_eq!(asset.asset(&bob_info.id).unwrap().units, 1);
+    assert_eq!(asset.asset(&dave_info.id).unwrap().units, 5);
 }
 
-pub fn store_data_tx(to: &AccountInfo, key: &str, data: &[u8]) -> Transaction {
-    let args = value!({
-        "key": key,
-        "data": serde_value::Value::Bytes(data.to_owned()),
-    });
+#[test]
+fn operator_transfers() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+
+    let mut asset = AssetClient::new(&mut app, &asset_info.id);
+    // 0. Asset initialization.
+    asset.init(asset_info, asset_init_args()).unwrap();
+    // 1. Mint some funds in Alice's account.
+    asset.mint(asset_info, &alice_info.id, 100).unwrap();
 
-    common::create_test_tx(
-        &to.id,
-        &to.pub_key,
-        &to.pvt_key,
-        *STORAGE_APP_HASH,
-        "store_data",
-        args,
-    )
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // 2. Alice authorizes Bob to pay Dave up to 15 units per day.
+    let mut limits = BTreeMap::new();
+    limits.insert(
+        asset_info.id.as_str(),
+        SpendingLimit {
+            units: 15,
+            period: 86400,
+        },
+    );
+    let args = SetOperatorArgs {
+        account: &bob_info.id,
+        limits,
+        destinations: vec![&dave_info.id],
+    };
+    storage.set_operator(alice_info, args).unwrap();
+    // 3. Bob transfers from Alice to Dave.
+    storage
+        .transfer(bob_info, &dave_info.id, &asset_info.id, 10)
+        .unwrap();
+    // 4. Bob transfers again from Alice to Dave. Shall fail for the limit.
+    assert_eq!(
+        storage.transfer(bob_info, &dave_info.id, &asset_info.id, 10),
+        fault("spending limit exceeded")
+    );
+    // 5. Bob transfers from Alice to himself. Shall fail for the destination.
+    assert_eq!(
+        storage.transfer(bob_info, &bob_info.id, &asset_info.id, 1),
+        fault("destination not allowed")
+    );
+    // 6. Alice revokes Bob.
+    storage.revoke_operator(alice_info, &bob_info.id).unwrap();
+    // 7. Bob transfers from Alice to Dave. Shall fail.
+    assert_eq!(
+        storage.transfer(bob_info, &dave_info.id, &asset_info.id, 1),
+        fault("not authorized")
+    );
+    // 8. Alice gets the spend log.
+    let records = storage.get_spends(alice_info, 0, 0).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].operator, bob_info.id);
+    assert_eq!(records[0].units, 10);
+
+    // Blockchain check.
+
+    let asset = AssetClient::new(&mut app, &asset_info.id);
+    assert_eq!(asset.asset(&alice_info.id).unwrap().units, 90);
+    assert_eq!(asset.asset(&dave_info.id).unwrap().units, 10);
 }
 
-pub fn load_data_tx(from: &AccountInfo, key: &str) -> Transaction {
-    let args = value!({
-        "key": key,
-    });
+#[test]
+fn account_data_management() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Store some data in Alice account.
+    storage.store_data(alice_info, "data", &[0, 1, 2]).unwrap();
+    // 1. Overwrite data in Alice account.
+    storage.store_data(alice_info, "data", &[1, 2, 3]).unwrap();
+    // 2. Load Alice's data.
+    assert_eq!(
+        storage.load_data(alice_info, "data").unwrap(),
+        vec![1, 2, 3]
+    );
+    // 3. Remove Alice's data.
+    storage.remove_data(alice_info, "data").unwrap();
+    // 4. Load Alice's data (expected an empty buffer).
+    assert!(storage.load_data(alice_info, "data").unwrap().is_empty());
+}
 
-    common::create_test_tx(
-        &from.id,
-        &from.pub_key,
-        &from.pvt_key,
-        *STORAGE_APP_HASH,
-        "load_data",
-        args,
-    )
+fn read_only() -> Permissions {
+    Permissions {
+        read: true,
+        write: false,
+        delete: false,
+    }
 }
 
-pub fn remove_data_tx(from: &AccountInfo, key: &str) -> Transaction {
-    let args = value!({
-        "key": key,
-    });
+fn write_only() -> Permissions {
+    Permissions {
+        read: false,
+        write: true,
+        delete: false,
+    }
+}
+
+#[test]
+fn account_data_access_control() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Store some shared data in Alice account.
+    storage
+        .store_data(alice_info, "shared:doc", &[1, 2, 3])
+        .unwrap();
+    // 1. Bob tries to load Alice's data. This shall fail.
+    assert_eq!(
+        storage.load_data(bob_info, "shared:doc").map(|_| ()),
+        fault("not authorized")
+    );
+    // 2. Alice grants to Bob the read permission on the shared prefix.
+    storage
+        .grant(alice_info, "shared:*", &bob_info.id, read_only())
+        .unwrap();
+    // 3. Bob loads Alice's data.
+    assert_eq!(
+        storage.load_data(bob_info, "shared:doc").unwrap(),
+        vec![1, 2, 3]
+    );
+    // 4. Dave tries to load Alice's data. This shall fail.
+    assert_eq!(
+        storage.load_data(dave_info, "shared:doc").map(|_| ()),
+        fault("not authorized")
+    );
+    // 5. Bob tries to overwrite Alice's data. This shall fail.
+    assert_eq!(
+        storage.store_data(bob_info, "shared:doc", &[4, 5, 6]),
+        fault("not authorized")
+    );
+    // 6. Alice tries to overwrite the ACL directly. This shall fail.
+    assert_eq!(
+        storage.store_data(alice_info, "__sys/acl:shared:*", &[4, 5, 6]),
+        fault("reserved key")
+    );
+    // 7. Alice grants to Bob the write permission on the doc key.
+    storage
+        .grant(alice_info, "shared:doc", &bob_info.id, write_only())
+        .unwrap();
+    // 8. Bob overwrites Alice's data.
+    storage
+        .store_data(bob_info, "shared:doc", &[4, 5, 6])
+        .unwrap();
+    // 9. Alice revokes the read permission on the shared prefix.
+    storage
+        .revoke(alice_info, "shared:*", &bob_info.id)
+        .unwrap();
+    // 10. Bob tries to load Alice's data. This shall fail.
+    assert_eq!(
+        storage.load_data(bob_info, "shared:doc").map(|_| ()),
+        fault("not authorized")
+    );
+    // 11. Alice loads her data.
+    assert_eq!(
+        storage.load_data(alice_info, "shared:doc").unwrap(),
+        vec![4, 5, 6]
+    );
+}
+
+#[test]
+fn account_data_key_listing() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
 
-    common::create_test_tx(
-        &from.id,
-        &from.pub_key,
-        &from.pvt_key,
-        *STORAGE_APP_HASH,
-        "remove_data",
-        args,
-    )
+    // 0. Store some data in Alice account.
+    storage.store_data(alice_info, "doc:2", &[2]).unwrap();
+    // 1. Store some data in Alice account.
+    storage.store_data(alice_info, "doc:1", &[1]).unwrap();
+    // 2. Store some data in Alice account.
+    storage.store_data(alice_info, "doc:3", &[3]).unwrap();
+    // 3. Store some data in Alice account.
+    storage.store_data(alice_info, "data", &[0]).unwrap();
+    // 4. List the first page of the documents.
+    let page = storage.list_keys(alice_info, "doc:", None, 2).unwrap();
+    assert_eq!(page.keys, vec!["doc:1", "doc:2"]);
+    assert_eq!(page.cursor.as_deref(), Some("doc:2"));
+    // 5. List the second page of the documents.
+    let page = storage
+        .list_keys(alice_info, "doc:", Some("doc:2"), 2)
+        .unwrap();
+    assert_eq!(page.keys, vec!["doc:3"]);
+    assert_eq!(page.cursor, None);
+    // 6. Load many documents at once.
+    let values = storage
+        .load_many(alice_info, vec!["doc:3", "doc:4", "data"])
+        .unwrap();
+    assert_eq!(values, vec![vec![3], vec![], vec![0]]);
 }
 
-fn create_data_management_txs() -> Vec<Transaction> {
+fn batch_op<'a>(op: &'a str, key: &'a str, data: &'a [u8]) -> BatchOp<'a> {
+    BatchOp { op, key, data }
+}
+
+fn precondition<'a>(key: &'a str, hash: &'a [u8]) -> Precondition<'a> {
+    Precondition { key, hash }
+}
+
+#[test]
+fn account_data_batch() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // sha256 of `[1]`
+    let hash =
+        hex::decode("4bf5122f344554c53bde2ebb8cd2b7e3d1600ad631c385a5d7cce23c7785459a").unwrap();
+
+    // 0. Store some data in Alice account.
+    storage.store_data(alice_info, "doc:a", &[1]).unwrap();
+    // 1. Update the document keys if `doc:a` has not changed.
+    let args = BatchArgs {
+        ops: vec![
+            batch_op("put", "doc:a", &[2]),
+            batch_op("put", "doc:b", &[3]),
+        ],
+        preconditions: vec![precondition("doc:a", &hash), precondition("doc:b", &[])],
+    };
+    storage.batch(alice_info, args).unwrap();
+    // 2. Update again the document with a stale precondition. This shall fail.
+    let args = BatchArgs {
+        ops: vec![batch_op("remove", "doc:b", &[])],
+        preconditions: vec![precondition("doc:a", &hash)],
+    };
+    assert_eq!(
+        storage.batch(alice_info, args),
+        fault("precondition failed")
+    );
+    // 3. Update the document with an invalid operation. This shall fail.
+    let args = BatchArgs {
+        ops: vec![
+            batch_op("remove", "doc:b", &[]),
+            batch_op("append", "doc:a", &[4]),
+        ],
+        preconditions: vec![],
+    };
+    assert_eq!(storage.batch(alice_info, args), fault("invalid operation"));
+    // 4. Load the document keys.
+    let values = storage
+        .load_many(alice_info, vec!["doc:a", "doc:b"])
+        .unwrap();
+    assert_eq!(values, vec![vec![2], vec![3]]);
+}
+
+#[test]
+fn account_data_versioning() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
     let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
-    vec![
-        // 0. Store some data in Alice account.
-        store_data_tx(alice_info, "data", &[0, 1, 2]),
-        // 1. Overwrite data in Alice account.
-        store_data_tx(alice_info, "data", &[1, 2, 3]),
-        // 2. Load Alice's data.
-        load_data_tx(alice_info, "data"),
-        // 3. Remove Alice's data.
-        remove_data_tx(alice_info, "data"),
-        // 4. Load Alice's data (expected an empty buffer).
-        load_data_tx(alice_info, "data"),
-    ]
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Store some data in Alice account.
+    storage.store_data(alice_info, "doc", &[1]).unwrap();
+    // 1. Load the data with its version.
+    let value = storage.load_data_versioned(alice_info, "doc").unwrap();
+    assert_eq!(value.version, 1);
+    assert_eq!(value.data, vec![1]);
+    // 2. Store the data if nobody else changed it.
+    let version = storage
+        .store_data_if_version(alice_info, "doc", &[2], 1)
+        .unwrap();
+    assert_eq!(version, 2);
+    // 3. Store the data with an outdated version. This shall fail.
+    assert_eq!(
+        storage
+            .store_data_if_version(alice_info, "doc", &[3], 1)
+            .map(|_| ()),
+        fault("version mismatch")
+    );
+    // 4. Bob tries to store the data. This shall fail.
+    assert_eq!(
+        storage
+            .store_data_if_version(bob_info, "doc", &[4], 2)
+            .map(|_| ()),
+        fault("not authorized")
+    );
+    // 5. Remove the data.
+    storage.remove_data(alice_info, "doc").unwrap();
+    // 6. Load the data with its version.
+    let value = storage.load_data_versioned(alice_info, "doc").unwrap();
+    assert_eq!(value.version, 3);
+    assert!(value.data.is_empty());
 }
 
-fn check_data_managements_rxs(rxs: Vec<Receipt>) {
-    // 0
-    assert_eq!(rxs[0].height, 0);
-    assert_eq!(rxs[0].index, 0);
-    assert!(rxs[0].success);
-    // 1
-    assert_eq!(rxs[1].height, 0);
-    assert_eq!(rxs[1].index, 1);
-    assert!(rxs[1].success);
-    // 2
-    assert_eq!(rxs[2].height, 0);
-    assert_eq!(rxs[2].index, 2);
-    assert!(rxs[2].success);
-    assert_eq!(rxs[2].returns, vec![1, 2, 3]);
-    // 3
-    assert_eq!(rxs[3].height, 0);
-    assert_eq!(rxs[3].index, 3);
-    assert!(rxs[3].success);
-    // 4
-    assert_eq!(rxs[4].height, 0);
-    assert_eq!(rxs[4].index, 4);
-    assert!(rxs[4].success);
-    assert!(rxs[4].returns.is_empty());
+#[test]
+fn account_data_notarization() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // sha256 of `b"0123456789"`
+    let hash =
+        hex::decode("84d89877f0d4041efb6bf91a16f0248f2fd573e6af05c19f96bedb9f882f7882").unwrap();
+    let key = format!("__sys/notary:{}", hex::encode(&hash));
+
+    // 0. Bob tries to notarize a document on Alice account. This shall fail.
+    assert_eq!(
+        storage.notarize(bob_info, &hash, Some("contract.pdf")),
+        fault("not authorized")
+    );
+    // 1. Alice grants to Bob the permission to notarize.
+    storage
+        .grant(alice_info, "__sys/notary:*", &bob_info.id, write_only())
+        .unwrap();
+    // 2. Bob notarizes a document on Alice account.
+    storage
+        .notarize(bob_info, &hash, Some("contract.pdf"))
+        .unwrap();
+    // 3. Alice notarizes again the same document. This shall fail.
+    assert_eq!(
+        storage.notarize(alice_info, &hash, Some("copy.pdf")),
+        fault("already notarized")
+    );
+    // 4. Alice tries to remove the record. This shall fail.
+    assert_eq!(storage.remove_data(alice_info, &key), fault("reserved key"));
+    // 5. Dave verifies the document.
+    let record = storage.verify(dave_info, &hash).unwrap();
+    assert_eq!(record.submitter, bob_info.id);
+    assert_eq!(record.metadata.as_deref(), Some("contract.pdf"));
+    // 6. Bob notarizes another document with too much metadata. This shall fail.
+    assert_eq!(
+        storage.notarize(bob_info, &[0; 32], Some(&"x".repeat(257))),
+        fault("metadata too large")
+    );
 }
 
 #[test]
-fn account_data_management() {
+fn account_data_quota() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Set a quota on Alice account data.
+    let quota = Quota {
+        max_keys: 2,
+        max_value_size: 4,
+        max_total_size: 6,
+    };
+    storage.set_quota(alice_info, quota).unwrap();
+    // 1. Store some data.
+    storage.store_data(alice_info, "a", &[1, 2, 3]).unwrap();
+    // 2. Store a value too large. This shall fail.
+    assert_eq!(
+        storage.store_data(alice_info, "b", &[1, 2, 3, 4, 5]),
+        fault("value too large")
+    );
+    // 3. Store some other data.
+    storage.store_data(alice_info, "b", &[1, 2, 3]).unwrap();
+    // 4. Store data in a third key. This shall fail.
+    assert_eq!(
+        storage.store_data(alice_info, "c", &[1]),
+        fault("too many keys")
+    );
+    // 5. Store data in a protected key. This shall fail.
+    assert_eq!(
+        storage.store_data(alice_info, "config", &[1]),
+        fault("protected key")
+    );
+    // 6. Remove some data.
+    storage.remove_data(alice_info, "b").unwrap();
+    // 7. Overwrite the protected key with the force flag.
+    storage
+        .force_store_data(alice_info, "config", &[1])
+        .unwrap();
+    // 8. Get the quota and the usage.
+    let report = storage.get_quota(alice_info).unwrap();
+    let expected = QuotaReport {
+        quota: QuotaLimits {
+            max_keys: 2,
+            max_value_size: 4,
+            max_total_size: 6,
+        },
+        usage: QuotaUsage {
+            keys: 2,
+            total_size: 4,
+        },
+    };
+    assert_eq!(report, expected);
+}
+
+#[test]
+fn account_data_documents() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Set a field of a new document.
+    storage
+        .set_field(alice_info, "doc", vec!["name"], value!("Alice"))
+        .unwrap();
+    // 1. Append some values to a new array.
+    storage
+        .append(alice_info, "doc", vec!["tags"], value!("a"))
+        .unwrap();
+    storage
+        .append(alice_info, "doc", vec!["tags"], value!("b"))
+        .unwrap();
+    // 3. Append a value to a field that is not an array. This shall fail.
+    assert_eq!(
+        storage.append(alice_info, "doc", vec!["name"], value!("c")),
+        fault("not an array")
+    );
+    // 4. Remove the first array element.
+    storage
+        .delete_field(alice_info, "doc", vec!["tags", "0"])
+        .unwrap();
+    // 5. Get a missing field. This shall fail.
+    assert_eq!(
+        storage
+            .get_field(alice_info, "doc", vec!["tags", "1"])
+            .map(|_| ()),
+        fault("field not found")
+    );
+    // 6. Load the whole document.
+    let buf = storage.load_data(alice_info, "doc").unwrap();
+    let doc: Value = rmp_deserialize(&buf).unwrap();
+    let expected = value!({
+        "name": "Alice",
+        "tags": ["b"],
+    });
+    assert_eq!(doc, expected);
+}
+
+#[test]
+fn account_blob_upload() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+    // sha256 of `b"0123456789"`
+    let hash =
+        hex::decode("84d89877f0d4041efb6bf91a16f0248f2fd573e6af05c19f96bedb9f882f7882").unwrap();
+
+    // 0. Begin the upload of a blob in two chunks.
+    assert!(storage.begin_upload(alice_info, &hash, 10, 2).unwrap());
+    // 1. Send the second chunk.
+    storage.put_chunk(alice_info, &hash, 1, b"56789").unwrap();
+    // 2. Commit the upload before sending all the chunks. This shall fail.
+    assert_eq!(
+        storage.commit_upload(alice_info, &hash),
+        fault("missing chunk")
+    );
+    // 3. Send the first chunk, corrupted.
+    storage.put_chunk(alice_info, &hash, 0, b"01235").unwrap();
+    // 4. Commit the upload with the corrupted chunk. This shall fail.
+    assert_eq!(
+        storage.commit_upload(alice_info, &hash),
+        fault("hash mismatch")
+    );
+    // 5. Send again the first chunk.
+    storage.put_chunk(alice_info, &hash, 0, b"01234").unwrap();
+    // 6. Commit the upload.
+    storage.commit_upload(alice_info, &hash).unwrap();
+    // 7. Begin again the upload of the same blob.
+    assert!(!storage.begin_upload(alice_info, &hash, 10, 1).unwrap());
+    // 8. Get the blob info.
+    let info = storage.blob_info(alice_info, &hash).unwrap();
+    assert_eq!(
+        info,
+        StoredBlob {
+            size: 10,
+            chunk_size: 65536,
+            chunks: 1,
+        }
+    );
+    // 9. Load a range of the blob.
+    assert_eq!(
+        storage.load_chunk(alice_info, &hash, 3, 4).unwrap(),
+        b"3456"
+    );
+}
+
+fn envelope<'a>(recipients: &[&'a str]) -> Envelope<'a> {
+    Envelope {
+        algorithm: "aes-256-gcm",
+        ciphertext: &[1, 2, 3],
+        keys: recipients
+            .iter()
+            .map(|recipient| (*recipient, ByteBuf::from(vec![0xab, 0xcd])))
+            .collect(),
+    }
+}
+
+#[test]
+fn account_data_envelopes() {
     // Instance the application.
     let mut app = TestApp::default();
 
-    // Create and execute transactions.
-    let txs = create_data_management_txs();
-    let rxs = app.exec_txs(txs);
-    check_data_managements_rxs(rxs);
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+    let bob_info = ACCOUNTS_INFO.get(BOB_ALIAS).unwrap();
+    let dave_info = ACCOUNTS_INFO.get(DAVE_ALIAS).unwrap();
+    let mut storage = StorageClient::new(&mut app, &alice_info.id);
+
+    // 0. Store an envelope without recipients. This shall fail.
+    assert_eq!(
+        storage.store_envelope(alice_info, "secret", envelope(&[])),
+        fault("invalid envelope")
+    );
+    // 1. Store an envelope for Alice and Bob.
+    let recipients = [alice_info.id.as_str(), bob_info.id.as_str()];
+    storage
+        .store_envelope(alice_info, "secret", envelope(&recipients))
+        .unwrap();
+    // 2. Bob adds Dave to the recipients. This shall fail.
+    assert_eq!(
+        storage.add_recipient(bob_info, "secret", &dave_info.id, &[0xef, 0x01]),
+        fault("not authorized")
+    );
+    // 3. Alice adds Dave to the recipients.
+    storage
+        .add_recipient(alice_info, "secret", &dave_info.id, &[0xef, 0x01])
+        .unwrap();
+    // 4. Alice revokes Bob.
+    storage
+        .revoke_recipient(alice_info, "secret", &bob_info.id)
+        .unwrap();
+    // 5. Alice revokes again Bob. This shall fail.
+    assert_eq!(
+        storage.revoke_recipient(alice_info, "secret", &bob_info.id),
+        fault("recipient not found")
+    );
+    // 6. Load a value that is not an envelope. This shall fail.
+    storage.store_data(alice_info, "plain", &[1, 2, 3]).unwrap();
+    assert_eq!(
+        storage.load_envelope(alice_info, "plain").map(|_| ()),
+        fault("invalid envelope")
+    );
+    // 8. Load the envelope.
+    let envelope = storage.load_envelope(alice_info, "secret").unwrap();
+    assert_eq!(envelope.algorithm, "aes-256-gcm");
+    assert_eq!(envelope.ciphertext, vec![1, 2, 3]);
+    let mut keys = BTreeMap::new();
+    keys.insert(alice_info.id.clone(), ByteBuf::from(vec![0xab, 0xcd]));
+    keys.insert(dave_info.id.clone(), ByteBuf::from(vec![0xef, 0x01]));
+    assert_eq!(envelope.keys, keys);
 }
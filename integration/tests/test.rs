@@ -24,11 +24,52 @@ use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
 use serde_value::Value;
 use std::collections::HashMap;
-use trinci_core::{base::serialize::rmp_deserialize, crypto::Hash};
+use trinci_core::{
+    base::serialize::{rmp_deserialize, rmp_serialize},
+    crypto::Hash,
+    db::Db,
+};
 use trinci_core::{Receipt, Transaction};
+use trinci_sdk::{
+    tai::{Asset, LockPrivilege, LockType},
+    Serializable,
+};
 
 const TEST_ALIAS: &str = "Test";
 const TEST2_ALIAS: &str = "Test2";
+const ASSET_ALIAS: &str = "FCK";
+const TEST3_ALIAS: &str = "Test3";
+const ALICE_ALIAS: &str = "Alice";
+
+/// Longest call chain executed while looking for the max call depth.
+const MAX_PROBED_DEPTH: usize = 32;
+
+/// Size of the buffers used to test the large payloads.
+const LARGE_PAYLOAD_SIZE: usize = 256 * 1024;
+
+/// Methods probing the sources of nondeterminism.
+const DETERMINISM_PROBES: &[&str] = &[
+    "get_random_sequence",
+    "get_hashmap",
+    "get_time",
+    "get_nan_bits",
+    "get_env",
+    "spawn_thread",
+    "get_instant",
+    "get_alloc_offsets",
+    "get_simd",
+];
+
+// Msgpack edge cases encodings.
+const NUMBERS_HEX: &str = "95cfffffffffffffffffd38000000000000000ffca3fc00000cbbfb999999999999a";
+const OPTIONS_HEX: &str = "92c0c0";
+const OPTIONS_MISSING_HEX: &str = "91c0";
+const OPTIONS_MAP_HEX: &str = "81a576616c7565c0";
+const EMPTY_MAP_HEX: &str = "80";
+const EMPTY_BYTES_HEX: &str = "92c40090";
+const MAPS_HEX: &str =
+    "92820080ceffffffff82ffa96d696e7573206f6e65cf7fffffffffffffffa36d617882c290c393010203";
+const EXT_HEX: &str = "d62a01020304";
 
 lazy_static! {
     static ref ACCOUNTS_INFO: HashMap<&'static str, AccountInfo> = {
@@ -38,6 +79,9 @@ lazy_static! {
             TEST2_ALIAS,
             AccountInfo::new(PUB_KEY2, PVT_KEY2, TEST2_ALIAS),
         );
+        map.insert(ASSET_ALIAS, AccountInfo::new(PUB_KEY3, PVT_KEY3, ""));
+        map.insert(ALICE_ALIAS, AccountInfo::new(PUB_KEY4, PVT_KEY4, ""));
+        map.insert(TEST3_ALIAS, AccountInfo::new(PUB_KEY5, PVT_KEY5, ""));
         map
     };
 }
@@ -45,6 +89,7 @@ lazy_static! {
 lazy_static! {
     pub static ref TEST_APP_HASH: Hash = app_hash("test.wasm").unwrap();
     pub static ref TEST2_APP_HASH: Hash = app_hash("service.wasm").unwrap();
+    pub static ref ASSET_APP_HASH: Hash = app_hash("asset.wasm").unwrap();
 }
 
 fn init_tx(test_info: &AccountInfo) -> Transaction {
@@ -366,6 +411,17 @@ fn check_drand_rxs(rxs: Vec<Receipt>) {
     assert_eq!(res, 958283);
 }
 
+#[test]
+fn test_contract_in_memory() {
+    // Instance the application with the in-memory database.
+    let mut app = TestApp::in_memory();
+
+    // Create and execute transactions.
+    let txs = create_txs();
+    let rxs = app.exec_txs(txs);
+    check_rxs(rxs);
+}
+
 #[test]
 fn test_drand() {
     // Instance the application.
@@ -376,3 +432,716 @@ fn test_drand() {
     let rxs = app.exec_txs(txs);
     check_drand_rxs(rxs);
 }
+
+/// Test context method result.
+#[derive(Deserialize, Debug, PartialEq)]
+struct ContextInfo {
+    depth: u16,
+    network: String,
+    owner: String,
+    caller: String,
+    method: String,
+    origin: String,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct AssetLockTestArgs {
+    asset: String,
+    to: String,
+    lock: LockType,
+}
+
+fn test_method_tx(test_info: &AccountInfo, method: &str, args: impl Serializable) -> Transaction {
+    common::create_test_tx(
+        &test_info.id,
+        &test_info.pub_key,
+        &test_info.pvt_key,
+        *TEST_APP_HASH,
+        method,
+        args,
+    )
+}
+
+fn store_asset_tx(test_info: &AccountInfo, account_info: &AccountInfo, units: u64) -> Transaction {
+    let args = value!({
+        "account": account_info.id,
+        "units": units,
+    });
+    test_method_tx(test_info, "test_hf_store_asset", args)
+}
+
+fn verify_tx(test_info: &AccountInfo, data: &[u8], signed_data: &[u8]) -> Transaction {
+    let keypair = p384_hex_keypair_to_keypair(&test_info.pub_key, &test_info.pvt_key);
+    let sign = keypair.sign(signed_data).unwrap();
+    let pk = p384_hex_key_to_public_key(&test_info.pub_key);
+    let args = value!({
+        "pk": serde_value::to_value(pk).unwrap(),
+        "data": Value::Bytes(data.to_vec()),
+        "sign": Value::Bytes(sign),
+    });
+    test_method_tx(test_info, "test_hf_verify", args)
+}
+
+fn notify_tx(test_info: &AccountInfo) -> Transaction {
+    let args = value!({
+        "message": "Hello!",
+    });
+    test_method_tx(test_info, "notify", args)
+}
+
+fn asset_init_tx(asset_info: &AccountInfo) -> Transaction {
+    let args = value!({
+        "name": ASSET_ALIAS,
+        "authorized": Vec::<&str>::new(),
+        "description": "My Cool Coin",
+        "url": "https://fck.you",
+        "max_units": 100_000,
+    });
+    common::create_test_tx(
+        &asset_info.id,
+        &asset_info.pub_key,
+        &asset_info.pvt_key,
+        *ASSET_APP_HASH,
+        "init",
+        args,
+    )
+}
+
+fn asset_mint_tx(asset_info: &AccountInfo, to_info: &AccountInfo, units: u64) -> Transaction {
+    let args = value!({
+        "to": to_info.id,
+        "units": units,
+    });
+    common::create_test_tx(
+        &asset_info.id,
+        &asset_info.pub_key,
+        &asset_info.pvt_key,
+        *ASSET_APP_HASH,
+        "mint",
+        args,
+    )
+}
+
+fn asset_transfer_tx(
+    test_info: &AccountInfo,
+    asset_info: &AccountInfo,
+    to_info: &AccountInfo,
+    units: u64,
+) -> Transaction {
+    let args = value!({
+        "asset": asset_info.id,
+        "to": to_info.id,
+        "units": units,
+    });
+    test_method_tx(test_info, "test_hf_asset_transfer", args)
+}
+
+fn asset_lock_tx(
+    test_info: &AccountInfo,
+    asset_info: &AccountInfo,
+    to_info: &AccountInfo,
+    lock: LockType,
+) -> Transaction {
+    let args = AssetLockTestArgs {
+        asset: asset_info.id.clone(),
+        to: to_info.id.clone(),
+        lock,
+    };
+    test_method_tx(test_info, "test_hf_asset_lock", args)
+}
+
+fn create_host_functions_txs() -> Vec<Transaction> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let test2_info = ACCOUNTS_INFO.get(TEST2_ALIAS).unwrap();
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+
+    vec![
+        // 0. Store some data
+        store_data_tx(test_info, "abc", &[1, 2, 3]),
+        // 1. Load the data
+        test_method_tx(test_info, "test_hf_load_data", "abc"),
+        // 2. Remove the data
+        test_method_tx(test_info, "test_hf_remove_data", "abc"),
+        // 3. Load the removed data
+        test_method_tx(test_info, "test_hf_load_data", "abc"),
+        // 4. Store the test asset in a foreign account
+        store_asset_tx(test_info, test2_info, 10),
+        // 5. Load the test asset from the foreign account
+        test_method_tx(test_info, "test_hf_load_asset", test2_info.id.as_str()),
+        // 6. Load the test asset from an account without it
+        test_method_tx(test_info, "test_hf_load_asset", asset_info.id.as_str()),
+        // 7. Verify a valid signature
+        verify_tx(test_info, b"hello", b"hello"),
+        // 8. Verify the signature of other data
+        verify_tx(test_info, b"hello", b"world"),
+        // 9. Get the block time
+        test_method_tx(test_info, "test_hf_get_block_time", value!(null)),
+        // 10. Get the call context
+        test_method_tx(test_info, "test_context", value!(null)),
+        // 11. Compute the sha256 of the packed args
+        test_method_tx(test_info, "test_sha256", value!([1, 2, 3])),
+        // 12. Emit the events
+        notify_tx(test_info),
+        // 13. Call a method via hf_call
+        test_method_tx(test_info, "nested_call", value!("hello")),
+        // 14. Init the asset and mint some units to the test account
+        asset_init_tx(asset_info),
+        asset_mint_tx(asset_info, test_info, 10),
+        // 16. Get the test account balance
+        test_method_tx(test_info, "test_hf_asset_balance", asset_info.id.as_str()),
+        // 17. Transfer some units from the test account
+        asset_transfer_tx(test_info, asset_info, test2_info, 4),
+        // 18. Transfer too many units. This shall fail
+        asset_transfer_tx(test_info, asset_info, test2_info, 100),
+        // 19. Lock the test account asset
+        asset_lock_tx(test_info, asset_info, test_info, LockType::Full),
+        // 20. Lock another account asset. This shall fail
+        asset_lock_tx(test_info, asset_info, test2_info, LockType::Full),
+        // 21. Transfer from the locked account. This shall fail
+        asset_transfer_tx(test_info, asset_info, test2_info, 1),
+    ]
+}
+
+fn check_host_functions_rxs(rxs: Vec<Receipt>) {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+
+    // 0. Store some data
+    assert!(rxs[0].success);
+    // 1. Load the data
+    assert!(rxs[1].success);
+    let res: Vec<u8> = rmp_deserialize(&rxs[1].returns).unwrap();
+    assert_eq!(res, vec![0x93, 1, 2, 3]);
+    // 2. Remove the data
+    assert!(rxs[2].success);
+    // 3. Load the removed data
+    assert!(rxs[3].success);
+    let res: Vec<u8> = rmp_deserialize(&rxs[3].returns).unwrap();
+    assert_eq!(res, Vec::<u8>::new());
+    // 4. Store the test asset in a foreign account
+    assert!(rxs[4].success);
+    // 5. Load the test asset from the foreign account
+    assert!(rxs[5].success);
+    let res: u64 = rmp_deserialize(&rxs[5].returns).unwrap();
+    assert_eq!(res, 10);
+    // 6. Load the test asset from an account without it
+    assert!(rxs[6].success);
+    let res: u64 = rmp_deserialize(&rxs[6].returns).unwrap();
+    assert_eq!(res, 0);
+    // 7. Verify a valid signature
+    assert!(rxs[7].success);
+    let res: bool = rmp_deserialize(&rxs[7].returns).unwrap();
+    assert!(res);
+    // 8. Verify the signature of other data
+    assert!(rxs[8].success);
+    let res: bool = rmp_deserialize(&rxs[8].returns).unwrap();
+    assert!(!res);
+    // 9. Get the block time
+    assert!(rxs[9].success);
+    rmp_deserialize::<u64>(&rxs[9].returns).unwrap();
+    // 10. Get the call context
+    assert!(rxs[10].success);
+    let res: ContextInfo = rmp_deserialize(&rxs[10].returns).unwrap();
+    let expected = ContextInfo {
+        depth: 0,
+        network: "skynet".to_string(),
+        owner: test_info.id.clone(),
+        caller: test_info.id.clone(),
+        method: "test_context".to_string(),
+        origin: test_info.id.clone(),
+    };
+    assert_eq!(res, expected);
+    // 11. Compute the sha256 of the packed args
+    assert!(rxs[11].success);
+    let res: Vec<u8> = rmp_deserialize(&rxs[11].returns).unwrap();
+    assert_eq!(
+        hex::encode(res),
+        "efd2ce5d1b243784f054828796128a9e3f85044cbfc21f7144a7a448ea3361e6"
+    );
+    // 12. Emit the events
+    assert!(rxs[12].success);
+    let events = rxs[12].events.as_ref().expect("no events emitted");
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].event_name, "event_a");
+    let data: Value = rmp_deserialize(&events[0].event_data).unwrap();
+    assert_eq!(data, value!({ "message": "Hello!" }));
+    assert_eq!(events[1].event_name, test_info.id);
+    assert_eq!(events[1].event_data, vec![0x93, 1, 2, 3]);
+    // 13. Call a method via hf_call
+    assert!(rxs[13].success);
+    let res: String = rmp_deserialize(&rxs[13].returns).unwrap();
+    assert_eq!(res, "hello");
+    // 14. Init the asset and mint some units to the test account
+    assert!(rxs[14].success);
+    assert!(rxs[15].success);
+    // 16. Get the test account balance
+    assert!(rxs[16].success);
+    let res: u64 = rmp_deserialize(&rxs[16].returns).unwrap();
+    assert_eq!(res, 10);
+    // 17. Transfer some units from the test account
+    assert!(rxs[17].success);
+    // 18. Transfer too many units. This shall fail
+    assert!(!rxs[18].success);
+    assert_eq!(
+        "smart contract fault: insufficient funds",
+        String::from_utf8_lossy(&rxs[18].returns)
+    );
+    // 19. Lock the test account asset
+    assert!(rxs[19].success);
+    // 20. Lock another account asset. This shall fail
+    assert!(!rxs[20].success);
+    assert_eq!(
+        "smart contract fault: not authorized",
+        String::from_utf8_lossy(&rxs[20].returns)
+    );
+    // 21. Transfer from the locked account. This shall fail
+    assert!(!rxs[21].success);
+    assert_eq!(
+        "smart contract fault: asset withdraw locked by contract",
+        String::from_utf8_lossy(&rxs[21].returns)
+    );
+}
+
+#[test]
+fn test_host_functions() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    // Create and execute transactions.
+    let txs = create_host_functions_txs();
+    let rxs = app.exec_txs(txs);
+    check_host_functions_rxs(rxs);
+
+    // Blockchain check.
+
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let test2_info = ACCOUNTS_INFO.get(TEST2_ALIAS).unwrap();
+    let asset_info = ACCOUNTS_INFO.get(ASSET_ALIAS).unwrap();
+
+    let account = app.account(&test2_info.id).unwrap();
+    assert_eq!(account.load_asset(&test_info.id), 10_u64.to_be_bytes());
+
+    let account = app.account(&test_info.id).unwrap();
+    let asset: Asset = rmp_deserialize(&account.load_asset(&asset_info.id)).unwrap();
+    assert_eq!(asset.units, 6);
+    assert_eq!(asset.lock, Some((LockPrivilege::Contract, LockType::Full)));
+
+    let account = app.account(&test2_info.id).unwrap();
+    let asset: Asset = rmp_deserialize(&account.load_asset(&asset_info.id)).unwrap();
+    assert_eq!(asset.units, 4);
+}
+
+fn call_chain_tx(
+    test_info: &AccountInfo,
+    caller_info: &AccountInfo,
+    accounts: &[&str],
+) -> Transaction {
+    let args = value!(accounts.to_vec());
+    common::create_test_tx(
+        &test_info.id,
+        &caller_info.pub_key,
+        &caller_info.pvt_key,
+        *TEST_APP_HASH,
+        "call_chain",
+        args,
+    )
+}
+
+fn store_and_call_tx(
+    test_info: &AccountInfo,
+    caller_info: &AccountInfo,
+    key: &str,
+    fail: bool,
+) -> Transaction {
+    nested_store_tx(test_info, caller_info, "store_and_call", key, fail)
+}
+
+fn store_and_catch_tx(
+    test_info: &AccountInfo,
+    caller_info: &AccountInfo,
+    key: &str,
+) -> Transaction {
+    nested_store_tx(test_info, caller_info, "store_and_catch", key, true)
+}
+
+fn nested_store_tx(
+    test_info: &AccountInfo,
+    caller_info: &AccountInfo,
+    method: &str,
+    key: &str,
+    fail: bool,
+) -> Transaction {
+    let args = value!({
+        "key": key,
+        "fail": fail,
+    });
+    common::create_test_tx(
+        &test_info.id,
+        &caller_info.pub_key,
+        &caller_info.pvt_key,
+        *TEST_APP_HASH,
+        method,
+        args,
+    )
+}
+
+/// Check the context of every level of a call chain.
+///
+/// `accounts` are the owners of the levels, the first one is called by `caller`.
+fn check_call_chain(contexts: &[ContextInfo], accounts: &[&str], caller: &str) {
+    assert_eq!(contexts.len(), accounts.len());
+    for (level, context) in contexts.iter().enumerate() {
+        let expected = ContextInfo {
+            depth: level as u16,
+            network: "skynet".to_string(),
+            owner: accounts[level].to_string(),
+            caller: if level == 0 {
+                caller.to_string()
+            } else {
+                accounts[level - 1].to_string()
+            },
+            method: "call_chain".to_string(),
+            origin: caller.to_string(),
+        };
+        assert_eq!(*context, expected, "level {}", level);
+    }
+}
+
+fn create_call_depth_txs() -> Vec<Transaction> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+
+    let mut txs = vec![
+        // 0. Init The test contract
+        init_tx(test_info),
+    ];
+    // 1... Call chains of growing depth on the test account itself
+    for depth in 0..=MAX_PROBED_DEPTH {
+        let accounts = vec![test_info.id.as_str(); depth];
+        txs.push(call_chain_tx(test_info, alice_info, &accounts));
+    }
+    txs
+}
+
+fn check_call_depth_rxs(rxs: Vec<Receipt>) {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+
+    // 0. Init The test contract
+    assert!(rxs[0].success);
+
+    // 1... The chains succeed up to the max depth and fail beyond it.
+    let rxs = &rxs[1..];
+    let max_depth = rxs
+        .iter()
+        .position(|rx| !rx.success)
+        .expect("no max call depth up to the probed one");
+    assert!(max_depth > 0, "a direct call failed");
+
+    for (depth, rx) in rxs[..max_depth].iter().enumerate() {
+        let contexts: Vec<ContextInfo> = rmp_deserialize(&rx.returns).unwrap();
+        let accounts = vec![test_info.id.as_str(); depth + 1];
+        check_call_chain(&contexts, &accounts, &alice_info.id);
+    }
+
+    // Every longer chain fails with the same error.
+    let error = String::from_utf8_lossy(&rxs[max_depth].returns).to_string();
+    println!("max call depth: {}, {}", max_depth - 1, error);
+    assert!(
+        error.starts_with("smart contract fault: "),
+        "unexpected error: {}",
+        error
+    );
+    for (depth, rx) in rxs.iter().enumerate().skip(max_depth) {
+        assert!(!rx.success, "depth {}", depth);
+        assert_eq!(
+            String::from_utf8_lossy(&rx.returns),
+            error,
+            "depth {}",
+            depth
+        );
+    }
+}
+
+#[test]
+fn test_call_depth() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    // Create and execute transactions.
+    let txs = create_call_depth_txs();
+    let rxs = app.exec_txs(txs);
+    check_call_depth_rxs(rxs);
+}
+
+fn create_reentrancy_txs() -> Vec<Transaction> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let test3_info = ACCOUNTS_INFO.get(TEST3_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+
+    vec![
+        // 0. Init The test contract on two accounts
+        init_tx(test_info),
+        init_tx(test3_info),
+        // 2. Call the other account, that calls back the first one
+        call_chain_tx(test_info, alice_info, &[&test3_info.id, &test_info.id]),
+        // 3. Store a key and fail in the nested call. This shall fail
+        store_and_call_tx(test_info, alice_info, "rollback", true),
+        // 4. Store a key and succeed in the nested call
+        store_and_call_tx(test_info, alice_info, "commit", false),
+        // 5. Store a key and catch the failure of the nested call
+        store_and_catch_tx(test_info, alice_info, "catch"),
+    ]
+}
+
+fn check_reentrancy_rxs(rxs: Vec<Receipt>) {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let test3_info = ACCOUNTS_INFO.get(TEST3_ALIAS).unwrap();
+    let alice_info = ACCOUNTS_INFO.get(ALICE_ALIAS).unwrap();
+
+    // 0. Init The test contract on two accounts
+    assert!(rxs[0].success);
+    assert!(rxs[1].success);
+    // 2. Call the other account, that calls back the first one
+    assert!(rxs[2].success);
+    let contexts: Vec<ContextInfo> = rmp_deserialize(&rxs[2].returns).unwrap();
+    let accounts = [
+        test_info.id.as_str(),
+        test3_info.id.as_str(),
+        test_info.id.as_str(),
+    ];
+    check_call_chain(&contexts, &accounts, &alice_info.id);
+    // 3. Store a key and fail in the nested call. This shall fail
+    assert!(!rxs[3].success);
+    assert_eq!(
+        "smart contract fault: nested call failure",
+        String::from_utf8_lossy(&rxs[3].returns)
+    );
+    // 4. Store a key and succeed in the nested call
+    assert!(rxs[4].success);
+    // 5. Store a key and catch the failure of the nested call
+    assert!(rxs[5].success);
+    let failed: bool = rmp_deserialize(&rxs[5].returns).unwrap();
+    assert!(failed);
+}
+
+fn run_reentrancy_and_rollback<D: Db>(app: &mut TestApp<D>) {
+    // Create and execute transactions.
+    let txs = create_reentrancy_txs();
+    let rxs = app.exec_txs(txs);
+    check_reentrancy_rxs(rxs);
+
+    // Blockchain check.
+
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+
+    // The keys stored before and within the failed nested call are rolled back.
+    for key in ["rollback", "rollback:nested"] {
+        let data = app.account_data(&test_info.id, key).unwrap_or_default();
+        assert_eq!(data, Vec::<u8>::new(), "{} not rolled back", key);
+    }
+    for key in ["commit", "commit:nested"] {
+        let data = app.account_data(&test_info.id, key).unwrap_or_default();
+        assert_eq!(data, vec![1], "{} not stored", key);
+    }
+    // The outer key persists, the key stored within the caught nested call is rolled back.
+    let data = app.account_data(&test_info.id, "catch").unwrap_or_default();
+    assert_eq!(data, vec![1], "catch not stored");
+    let data = app
+        .account_data(&test_info.id, "catch:nested")
+        .unwrap_or_default();
+    assert_eq!(data, Vec::<u8>::new(), "catch:nested not rolled back");
+}
+
+#[test]
+fn test_reentrancy_and_rollback() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    run_reentrancy_and_rollback(&mut app);
+}
+
+#[test]
+fn test_reentrancy_and_rollback_in_memory() {
+    // Instance the application with the in-memory database, the nested
+    // calls exercise its backup and restore levels.
+    let mut app = TestApp::in_memory();
+
+    run_reentrancy_and_rollback(&mut app);
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct BytesArgs {
+    #[serde(with = "serde_bytes")]
+    bin: Vec<u8>,
+    array: Vec<u8>,
+}
+
+fn raw_tx(test_info: &AccountInfo, method: &str, args: Vec<u8>) -> Transaction {
+    common::create_raw_test_tx(
+        &test_info.id,
+        &test_info.pub_key,
+        &test_info.pvt_key,
+        *TEST_APP_HASH,
+        method,
+        args,
+    )
+}
+
+fn large_payload() -> Vec<u8> {
+    let args = BytesArgs {
+        bin: vec![0xAA; LARGE_PAYLOAD_SIZE],
+        array: vec![0x55; LARGE_PAYLOAD_SIZE],
+    };
+    trinci_sdk::rmp_serialize(&args).unwrap()
+}
+
+/// Malformed `echo_numbers` arguments.
+fn malformed_payloads() -> Vec<Vec<u8>> {
+    let mut truncated = hex::decode(NUMBERS_HEX).unwrap();
+    truncated.pop();
+    vec![
+        // Empty buffer
+        vec![],
+        // Truncated array
+        truncated,
+        // Reserved marker
+        vec![0xc1],
+        // String in place of an integer
+        hex::decode("95a3666f6fd38000000000000000ffca3fc00000cbbfb999999999999a").unwrap(),
+        // Negative integer in place of an unsigned one
+        hex::decode("95ffd38000000000000000ffca3fc00000cbbfb999999999999a").unwrap(),
+        // Out of range integer
+        hex::decode("95cfffffffffffffffffd38000000000000000cc80ca3fc00000cbbfb999999999999a")
+            .unwrap(),
+    ]
+}
+
+fn create_msgpack_txs() -> Vec<Transaction> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+    let hex_tx = |method, args| raw_tx(test_info, method, hex::decode(args).unwrap());
+
+    let mut txs = vec![
+        // 0. Init The test contract
+        init_tx(test_info),
+        // 1. Integer limits and floats
+        hex_tx("echo_numbers", NUMBERS_HEX),
+        // 2. Missing trailing option
+        hex_tx("echo_options", OPTIONS_MISSING_HEX),
+        // 3. Struct as map with a nil option
+        hex_tx("echo_options", OPTIONS_MAP_HEX),
+        // 4. Generic map with a nil value
+        hex_tx("echo_generic", OPTIONS_MAP_HEX),
+        // 5. Generic map with a missing value
+        hex_tx("echo_generic", EMPTY_MAP_HEX),
+        // 6. Empty bin and empty array
+        hex_tx("echo_bytes", EMPTY_BYTES_HEX),
+        // 7. Generic empty bin and empty array
+        hex_tx("echo_generic", EMPTY_BYTES_HEX),
+        // 8. Nested maps with non-string keys
+        hex_tx("echo_maps", MAPS_HEX),
+        // 9. Extension type
+        hex_tx("echo_ext", EXT_HEX),
+        // 10. Large typed payload
+        raw_tx(test_info, "echo_bytes", large_payload()),
+        // 11. Large packed payload
+        raw_tx(test_info, "echo_packed", large_payload()),
+    ];
+    // 12... Malformed input
+    for args in malformed_payloads() {
+        txs.push(raw_tx(test_info, "echo_numbers", args));
+    }
+    txs
+}
+
+fn check_msgpack_rxs(rxs: Vec<Receipt>) {
+    // 0. Init The test contract
+    assert!(rxs[0].success);
+    // 1. Integer limits and floats
+    assert!(rxs[1].success);
+    assert_eq!(hex::encode(&rxs[1].returns), NUMBERS_HEX);
+    // 2. Missing trailing option
+    assert!(rxs[2].success);
+    assert_eq!(hex::encode(&rxs[2].returns), OPTIONS_HEX);
+    // 3. Struct as map with a nil option
+    assert!(rxs[3].success);
+    assert_eq!(hex::encode(&rxs[3].returns), OPTIONS_HEX);
+    // 4. Generic map with a nil value
+    assert!(rxs[4].success);
+    assert_eq!(hex::encode(&rxs[4].returns), OPTIONS_MAP_HEX);
+    // 5. Generic map with a missing value
+    assert!(rxs[5].success);
+    assert_eq!(hex::encode(&rxs[5].returns), EMPTY_MAP_HEX);
+    // 6. Empty bin and empty array
+    assert!(rxs[6].success);
+    assert_eq!(hex::encode(&rxs[6].returns), EMPTY_BYTES_HEX);
+    // 7. Generic empty bin and empty array
+    assert!(rxs[7].success);
+    assert_eq!(hex::encode(&rxs[7].returns), EMPTY_BYTES_HEX);
+    // 8. Nested maps with non-string keys
+    assert!(rxs[8].success);
+    assert_eq!(hex::encode(&rxs[8].returns), MAPS_HEX);
+    // 9. Extension type
+    assert!(rxs[9].success);
+    assert_eq!(hex::encode(&rxs[9].returns), EXT_HEX);
+    // 10. Large typed payload
+    assert!(rxs[10].success);
+    assert_eq!(rxs[10].returns, large_payload());
+    // 11. Large packed payload
+    assert!(rxs[11].success);
+    assert_eq!(rxs[11].returns, large_payload());
+    // 12... Malformed input
+    for rx in rxs[12..].iter() {
+        assert!(!rx.success);
+        assert_eq!(
+            "smart contract fault: deserialization failure",
+            String::from_utf8_lossy(&rx.returns)
+        );
+    }
+}
+
+#[test]
+fn test_msgpack_edge_cases() {
+    // Instance the application.
+    let mut app = TestApp::default();
+
+    // Create and execute transactions.
+    let txs = create_msgpack_txs();
+    let rxs = app.exec_txs(txs);
+    check_msgpack_rxs(rxs);
+}
+
+fn create_determinism_txs() -> Vec<Transaction> {
+    let test_info = ACCOUNTS_INFO.get(TEST_ALIAS).unwrap();
+
+    let mut txs = vec![
+        // 0. Init The test contract
+        init_tx(test_info),
+    ];
+    // 1... Nondeterminism probes
+    for method in DETERMINISM_PROBES {
+        txs.push(test_method_tx(test_info, method, value!(null)));
+    }
+    txs
+}
+
+#[test]
+fn test_determinism() {
+    // The same transactions are executed by two fresh instances.
+    let txs = create_determinism_txs();
+
+    let mut app = TestApp::default();
+    let rxs = app.exec_txs(txs.clone());
+    let mut other_app = TestApp::default();
+    let other_rxs = other_app.exec_txs(txs);
+
+    assert!(rxs[0].success);
+    assert_eq!(rxs.len(), other_rxs.len());
+    for (i, (rx, other_rx)) in rxs.iter().zip(other_rxs.iter()).enumerate().skip(1) {
+        assert_eq!(
+            rmp_serialize(rx).unwrap(),
+            rmp_serialize(other_rx).unwrap(),
+            "{} is not deterministic",
+            DETERMINISM_PROBES[i - 1]
+        );
+    }
+}